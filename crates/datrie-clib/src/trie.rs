@@ -159,7 +159,7 @@ pub unsafe extern "C" fn trie_store_if_absent(
     }
     let trie = unsafe { &mut *trie };
     let alpha_key = AlphaStr::from_ptr(key);
-    trie.store(&alpha_key, data) as Bool
+    trie.store_if_absent(&alpha_key, data) as Bool
 }
 #[no_mangle]
 pub unsafe extern "C" fn trie_delete(trie: *mut Trie, key: *const AlphaChar) -> Bool {
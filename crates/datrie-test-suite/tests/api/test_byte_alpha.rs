@@ -62,7 +62,8 @@ fn test_byte_alpha() -> anyhow::Result<()> {
         }
 
         msg_step("Storing key to test trie");
-        let key = &[0xff, 0xff, 0];
+        // char 0 takes trie char 1, so 0xfe is the last byte with a code
+        let key = &[0xfe, 0xfe, 0];
         assert_eq!(
             trie_store(test_trie, key.as_ptr(), TEST_DATA),
             1,
@@ -81,6 +82,15 @@ fn test_byte_alpha() -> anyhow::Result<()> {
             "Retrieved data = {}, not {}\n",
             data, TEST_DATA
         );
+
+        msg_step("Rejecting a key past the last trie char");
+        let key = &[0xff, 0xff, 0];
+        assert_eq!(
+            trie_store(test_trie, key.as_ptr(), TEST_DATA),
+            0,
+            "Key past the last trie char is stored\n"
+        );
+        assert_eq!(trie_retrieve(test_trie, key.as_ptr(), &mut data), 0);
     }
     Ok(())
 }
@@ -0,0 +1,310 @@
+use std::collections::VecDeque;
+
+use crate::trie::{TrieChar, TrieIndex};
+use crate::{AlphaChar, AlphaMap, Trie, TrieData};
+
+/// A multi-pattern matcher for all the keys of a [`Trie`].
+///
+/// The automaton has one state per branch node of the double-array and one
+/// per position in the tail suffixes, with failure links computed over
+/// them. Text is scanned in a single pass, either from a slice or from any
+/// iterator of alpha chars. Characters outside the alpha map never match,
+/// and the empty key is never reported.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    alpha_map: AlphaMap,
+    states: Vec<AcState>,
+}
+
+/// An occurrence of a key in the scanned text.
+///
+/// `start` and `end` are char offsets, with `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextMatch {
+    pub start: usize,
+    pub end: usize,
+    pub data: TrieData,
+}
+
+/// How [`AhoCorasick::find_iter`] reports matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchKind {
+    /// Every occurrence of every key, ordered by end offset, longest first.
+    #[default]
+    Overlapping,
+    /// Non-overlapping matches, preferring the leftmost start and then the
+    /// longest key.
+    LeftmostLongest,
+}
+
+const ROOT: u32 = 0;
+
+#[derive(Debug, Clone)]
+struct AcState {
+    /// Goto transitions, sorted by trie char.
+    next: Vec<(TrieChar, u32)>,
+    fail: u32,
+    /// Nearest state along the failure links that has an output.
+    dict: Option<u32>,
+    depth: u32,
+    /// Data of the key ending at this state.
+    output: Option<TrieData>,
+}
+
+/// A position in the trie: a DArray node, or a char in a tail suffix.
+#[derive(Clone, Copy)]
+enum TriePos {
+    Node(TrieIndex),
+    Tail(TrieIndex, usize),
+}
+
+impl AhoCorasick {
+    pub fn new(trie: &Trie) -> AhoCorasick {
+        let mut states = vec![AcState {
+            next: Vec::new(),
+            fail: ROOT,
+            dict: None,
+            depth: 0,
+            output: None,
+        }];
+        // build the goto tree breadth first, so parents come before children
+        let mut queue = VecDeque::from([(ROOT, TriePos::Node(trie.da.get_root()))]);
+        while let Some((id, pos)) = queue.pop_front() {
+            let (edges, output) = Self::expand(trie, pos);
+            if id != ROOT {
+                states[id as usize].output = output;
+            }
+            let depth = states[id as usize].depth + 1;
+            for (c, child_pos) in edges {
+                let child = states.len() as u32;
+                states.push(AcState {
+                    next: Vec::new(),
+                    fail: ROOT,
+                    dict: None,
+                    depth,
+                    output: None,
+                });
+                states[id as usize].next.push((c, child));
+                queue.push_back((child, child_pos));
+            }
+        }
+        let mut ac = AhoCorasick {
+            alpha_map: trie.alpha_map.clone(),
+            states,
+        };
+        ac.link_failures();
+        ac
+    }
+
+    /// The goto edges and the output of trie position `pos`.
+    fn expand(trie: &Trie, pos: TriePos) -> (Vec<(TrieChar, TriePos)>, Option<TrieData>) {
+        let (t, idx) = match pos {
+            TriePos::Node(s) if trie.da.get_base(s) < 0 => (-trie.da.get_base(s), 0),
+            TriePos::Node(s) => {
+                let base = trie.da.get_base(s);
+                let mut edges = Vec::new();
+                let mut output = None;
+                if base > 0 {
+                    let syms = trie.da.output_symbols(s);
+                    for i in 0..syms.num() {
+                        let c = syms.get(i);
+                        if c == 0 {
+                            // the terminator leads to a separate node with
+                            // an empty suffix
+                            output = trie.tail.get_data(-trie.da.get_base(base));
+                        } else {
+                            edges.push((c, TriePos::Node(base + c as TrieIndex)));
+                        }
+                    }
+                }
+                return (edges, output);
+            }
+            TriePos::Tail(t, idx) => (t, idx),
+        };
+        let suffix = trie.tail.get_suffix(t).map_or(&[][..], |s| s.to_bytes());
+        match suffix.get(idx) {
+            Some(&c) => (vec![(c, TriePos::Tail(t, idx + 1))], None),
+            None => (Vec::new(), trie.tail.get_data(t)),
+        }
+    }
+
+    fn link_failures(&mut self) {
+        let mut queue = VecDeque::from([ROOT]);
+        while let Some(id) = queue.pop_front() {
+            for i in 0..self.states[id as usize].next.len() {
+                let (c, child) = self.states[id as usize].next[i];
+                let fail = if id == ROOT {
+                    ROOT
+                } else {
+                    let mut f = self.states[id as usize].fail;
+                    loop {
+                        if let Some(to) = self.goto(f, c) {
+                            break to;
+                        }
+                        if f == ROOT {
+                            break ROOT;
+                        }
+                        f = self.states[f as usize].fail;
+                    }
+                };
+                let fail_state = &self.states[fail as usize];
+                let dict = if fail_state.output.is_some() {
+                    Some(fail)
+                } else {
+                    fail_state.dict
+                };
+                let state = &mut self.states[child as usize];
+                state.fail = fail;
+                state.dict = dict;
+                queue.push_back(child);
+            }
+        }
+    }
+
+    fn goto(&self, id: u32, c: TrieChar) -> Option<u32> {
+        let next = &self.states[id as usize].next;
+        next.binary_search_by_key(&c, |&(c, _)| c)
+            .ok()
+            .map(|i| next[i].1)
+    }
+
+    /// The state after reading `ac` in state `id`.
+    fn step(&self, mut id: u32, ac: AlphaChar) -> u32 {
+        let c = match self.alpha_map.char_to_trie(ac) {
+            Some(c) if c != 0 => c as TrieChar,
+            _ => return ROOT,
+        };
+        loop {
+            if let Some(to) = self.goto(id, c) {
+                return to;
+            }
+            if id == ROOT {
+                return ROOT;
+            }
+            id = self.states[id as usize].fail;
+        }
+    }
+
+    /// Scan `text` for keys, reporting matches as `kind` says.
+    ///
+    /// `text` may be a slice iterator or any other stream of alpha chars;
+    /// matches are produced as soon as they are settled.
+    pub fn find_iter<I>(&self, text: I, kind: MatchKind) -> Matches<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = AlphaChar>,
+    {
+        Matches {
+            ac: self,
+            text: text.into_iter(),
+            kind,
+            state: ROOT,
+            pos: 0,
+            pending: VecDeque::new(),
+            resume: 0,
+        }
+    }
+
+    /// All occurrences of keys in `text`, see [`MatchKind::Overlapping`].
+    pub fn find_overlapping(&self, text: &[AlphaChar]) -> Vec<TextMatch> {
+        self.find_iter(text.iter().copied(), MatchKind::Overlapping)
+            .collect()
+    }
+
+    /// Non-overlapping occurrences of keys in `text`, see
+    /// [`MatchKind::LeftmostLongest`].
+    pub fn find_leftmost_longest(&self, text: &[AlphaChar]) -> Vec<TextMatch> {
+        self.find_iter(text.iter().copied(), MatchKind::LeftmostLongest)
+            .collect()
+    }
+}
+
+/// Iterator over the matches in a text, see [`AhoCorasick::find_iter`].
+pub struct Matches<'a, I> {
+    ac: &'a AhoCorasick,
+    text: I,
+    kind: MatchKind,
+    state: u32,
+    /// Number of chars read.
+    pos: usize,
+    /// Matches found but not yet returned.
+    ///
+    /// In leftmost-longest mode these are candidates that a later match
+    /// may still beat.
+    pending: VecDeque<TextMatch>,
+    /// Leftmost-longest mode only: where the next match may start.
+    resume: usize,
+}
+
+impl<I: Iterator<Item = AlphaChar>> Matches<'_, I> {
+    /// Read one char and collect the matches ending after it.
+    ///
+    /// Returns `false` at the end of the text.
+    fn advance(&mut self) -> bool {
+        let Some(ac) = self.text.next() else {
+            return false;
+        };
+        self.state = self.ac.step(self.state, ac);
+        self.pos += 1;
+        let mut id = Some(self.state);
+        while let Some(cur) = id {
+            let state = &self.ac.states[cur as usize];
+            if let Some(data) = state.output {
+                let found = TextMatch {
+                    start: self.pos - state.depth as usize,
+                    end: self.pos,
+                    data,
+                };
+                if self.kind == MatchKind::Overlapping || found.start >= self.resume {
+                    self.pending.push_back(found);
+                }
+            }
+            id = state.dict;
+        }
+        true
+    }
+
+    /// Leftmost-longest mode: the best pending candidate, if no match still
+    /// to come can start at or before it.
+    fn settled(&mut self, at_end: bool) -> Option<TextMatch> {
+        let best = self
+            .pending
+            .iter()
+            .copied()
+            .filter(|m| m.start >= self.resume)
+            .min_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)))?;
+        // every key prefix being matched starts at or after this offset
+        let live_start = self.pos - self.ac.states[self.state as usize].depth as usize;
+        if !at_end && live_start <= best.start {
+            return None;
+        }
+        self.resume = best.end;
+        let resume = self.resume;
+        self.pending.retain(|m| m.start >= resume);
+        Some(best)
+    }
+}
+
+impl<I: Iterator<Item = AlphaChar>> Iterator for Matches<'_, I> {
+    type Item = TextMatch;
+
+    fn next(&mut self) -> Option<TextMatch> {
+        match self.kind {
+            MatchKind::Overlapping => loop {
+                if let Some(found) = self.pending.pop_front() {
+                    return Some(found);
+                }
+                if !self.advance() {
+                    return None;
+                }
+            },
+            MatchKind::LeftmostLongest => loop {
+                if let Some(found) = self.settled(false) {
+                    return Some(found);
+                }
+                if !self.advance() {
+                    return self.settled(true);
+                }
+            },
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use ::libc;
 use byteorder::{BigEndian, WriteBytesExt};
+use std::cmp::Ordering;
 use std::io::SeekFrom;
 
 use crate::{
@@ -16,14 +17,17 @@ pub type AlphaChar = u32;
 pub type TrieChar = libc::c_uchar;
 pub type TrieIndex = i32;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct AlphaMap {
     ranges: Vec<AlphaRange>,
+    /// Symbols that get the lowest trie-char codes, in this order.
+    /// Empty means plain code-point order.
+    order: Vec<AlphaChar>,
     alpha_to_trie_map: Vec<TrieIndex>,
     trie_to_alpha_map: Vec<AlphaChar>,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct AlphaRange {
     pub begin: AlphaChar,
     pub end: AlphaChar,
@@ -50,6 +54,9 @@ impl AlphaMap {
     /// Magic number signature for the AlphaMap binary format (0xd9fcd9fc)
     /// Introduced in the initial binary serialization format
     const SIGNATURE: u32 = 0xd9fcd9fc;
+    /// Magic number signature for an AlphaMap with a custom symbol order (0xd9fdd9fd)
+    /// Same layout as [`Self::SIGNATURE`], followed by the symbol count and the symbols
+    const ORDERED_SIGNATURE: u32 = 0xd9fdd9fd;
     const SIGNATURE_SIZE: usize = 4;
     pub fn fread_bin_safe<R: ReadSeekExt>(reader: &mut R) -> DatrieResult<AlphaMap> {
         let save_pos = reader.stream_position()?;
@@ -63,7 +70,7 @@ impl AlphaMap {
     fn do_fread_bin_safe<R: ReadExt>(reader: &mut R) -> DatrieResult<AlphaMap> {
         let mut sig = 0;
         reader.read_uint32(&mut sig)?;
-        if sig != Self::SIGNATURE {
+        if sig != Self::SIGNATURE && sig != Self::ORDERED_SIGNATURE {
             return Err(DatrieError::new(
                 ErrorKind::InvalidFileSignature,
                 format!("Unexpected AlphaMapOld signature: '{}'", sig),
@@ -80,6 +87,18 @@ impl AlphaMap {
             reader.read_int32(&mut e)?;
             alpha_map.add_range_only(b as AlphaChar, e as AlphaChar)?;
         }
+        if sig == Self::ORDERED_SIGNATURE {
+            let mut n_order = 0;
+            reader.read_int32(&mut n_order)?;
+            let mut order = Vec::with_capacity(n_order.clamp(0, 0xff) as usize);
+            for _ in 0..n_order {
+                let mut c = 0;
+                reader.read_int32(&mut c)?;
+                order.push(c as AlphaChar);
+            }
+            alpha_map.check_order(&order)?;
+            alpha_map.order = order;
+        }
         alpha_map.recalc_work_area();
         Ok(alpha_map)
     }
@@ -88,25 +107,44 @@ impl AlphaMap {
 impl AlphaMap {
     pub(crate) fn get_serialized_size(&self) -> usize {
         let ranges_count = self.get_total_ranges();
+        let order_size = if self.order.is_empty() {
+            0
+        } else {
+            ::core::mem::size_of::<i32>() + ::core::mem::size_of::<AlphaChar>() * self.order.len()
+        };
         Self::SIGNATURE_SIZE
             + ::core::mem::size_of::<i32>()
             + (::core::mem::size_of::<AlphaChar>() * 2 * ranges_count)
+            + order_size
+    }
+    fn signature(&self) -> u32 {
+        if self.order.is_empty() {
+            Self::SIGNATURE
+        } else {
+            Self::ORDERED_SIGNATURE
+        }
     }
     fn get_total_ranges(&self) -> usize {
         self.ranges.len()
     }
     pub(crate) fn serialize(&self, buf: &mut dyn std::io::Write) -> DatrieResult<()> {
-        buf.write_i32::<BigEndian>(Self::SIGNATURE as i32)?;
+        buf.write_i32::<BigEndian>(self.signature() as i32)?;
         buf.write_i32::<BigEndian>(self.get_total_ranges() as i32)?;
         for range in &self.ranges {
             buf.write_i32::<BigEndian>(range.begin as i32)?;
             buf.write_i32::<BigEndian>(range.end as i32)?;
         }
+        if !self.order.is_empty() {
+            buf.write_i32::<BigEndian>(self.order.len() as i32)?;
+            for &c in &self.order {
+                buf.write_i32::<BigEndian>(c as i32)?;
+            }
+        }
 
         Ok(())
     }
     pub(crate) fn serialize_to_slice(&self, mut buf: &mut [u8]) -> DatrieResult<usize> {
-        buf.write_i32::<BigEndian>(Self::SIGNATURE as i32).unwrap();
+        buf.write_i32::<BigEndian>(self.signature() as i32).unwrap();
         buf.write_i32::<BigEndian>(self.get_total_ranges() as i32)?;
         let mut written = 8;
         for range in &self.ranges {
@@ -114,6 +152,14 @@ impl AlphaMap {
             buf.write_i32::<BigEndian>(range.end as i32)?;
             written += 8;
         }
+        if !self.order.is_empty() {
+            buf.write_i32::<BigEndian>(self.order.len() as i32)?;
+            written += 4;
+            for &c in &self.order {
+                buf.write_i32::<BigEndian>(c as i32)?;
+                written += 4;
+            }
+        }
 
         Ok(written)
     }
@@ -200,18 +246,18 @@ impl AlphaMap {
         // dbg!(&n_alpha);
         self.alpha_to_trie_map = vec![Self::ERROR_CHAR; n_alpha as usize];
         self.trie_to_alpha_map = vec![Self::ERROR_CHAR as u32; n_trie as usize];
-        let mut trie_char = 0;
-        for range in &self.ranges {
-            let mut a = range.begin;
-            while a <= range.end {
-                if trie_char == 0 {
-                    trie_char += 1;
-                }
-                self.alpha_to_trie_map[a.wrapping_sub(alpha_begin) as usize] = trie_char;
-                self.trie_to_alpha_map[trie_char as usize] = a;
-                trie_char += 1;
-                a += 1;
+        // symbols of the custom order come first, the rest follow in
+        // code-point order
+        let mut trie_char = 1;
+        let all_chars = self.ranges.iter().flat_map(|r| r.begin..=r.end);
+        for a in self.order.iter().copied().chain(all_chars) {
+            let slot = &mut self.alpha_to_trie_map[a.wrapping_sub(alpha_begin) as usize];
+            if *slot != Self::ERROR_CHAR {
+                continue;
             }
+            *slot = trie_char;
+            self.trie_to_alpha_map[trie_char as usize] = a;
+            trie_char += 1;
         }
         while trie_char < n_trie as i32 {
             self.trie_to_alpha_map[trie_char as usize] = 1;
@@ -220,18 +266,97 @@ impl AlphaMap {
         self.trie_to_alpha_map[0] = 0;
     }
     const ERROR_CHAR: TrieIndex = 0x7fffffff;
+
+    /// Build an alpha map holding exactly `symbols`, with trie-char codes
+    /// assigned in the given order.
+    ///
+    /// Iterating a trie built on this map yields keys in this collation
+    /// order instead of code-point order.
+    pub fn from_ordered_symbols(symbols: &[AlphaChar]) -> DatrieResult<AlphaMap> {
+        if symbols.is_empty() {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "from_ordered_symbols: no symbols given".into(),
+            ));
+        }
+        let mut alpha_map = AlphaMap::default();
+        for &c in symbols {
+            alpha_map.add_range_only(c, c)?;
+        }
+        alpha_map.set_symbol_order(symbols)?;
+        Ok(alpha_map)
+    }
+
+    /// Give `symbols` the lowest trie-char codes, in the given order.
+    ///
+    /// The symbols must already be covered by the ranges of the map. Symbols
+    /// not listed follow in code-point order.
+    pub fn set_symbol_order(&mut self, symbols: &[AlphaChar]) -> DatrieResult<()> {
+        self.check_order(symbols)?;
+        self.order = symbols.to_vec();
+        self.recalc_work_area();
+        Ok(())
+    }
+
+    /// Order all symbols of the map with the comparator `compare`.
+    pub fn sort_symbols_by<F>(&mut self, mut compare: F) -> DatrieResult<()>
+    where
+        F: FnMut(&AlphaChar, &AlphaChar) -> Ordering,
+    {
+        let mut symbols: Vec<AlphaChar> =
+            self.ranges.iter().flat_map(|r| r.begin..=r.end).collect();
+        symbols.sort_by(&mut compare);
+        self.set_symbol_order(&symbols)
+    }
+
+    /// The custom symbol order, empty if the map uses code-point order.
+    pub fn symbol_order(&self) -> &[AlphaChar] {
+        &self.order
+    }
+
+    fn check_order(&self, symbols: &[AlphaChar]) -> DatrieResult<()> {
+        if symbols.len() > TrieChar::MAX as usize {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "symbol order has {} symbols, at most {} are supported",
+                    symbols.len(),
+                    TrieChar::MAX
+                ),
+            ));
+        }
+        for (i, &c) in symbols.iter().enumerate() {
+            if !self.ranges.iter().any(|r| r.begin <= c && c <= r.end) {
+                return Err(DatrieError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("symbol {:#x} in symbol order is not in the alpha map", c),
+                ));
+            }
+            if symbols[..i].contains(&c) {
+                return Err(DatrieError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("symbol {:#x} occurs twice in symbol order", c),
+                ));
+            }
+        }
+        Ok(())
+    }
     pub(crate) fn char_to_trie(&self, ac: AlphaChar) -> Option<TrieIndex> {
         // dbg!(&ac);
         if ac == 0 {
             return Some(0);
         }
-        let alpha_begin = self.ranges[0].begin;
+        let alpha_begin = self.ranges.first()?.begin;
         // dbg!(&alpha_begin);
         let alpha_end = self.ranges[self.ranges.len() - 1].end;
         // dbg!(&alpha_end);
         if alpha_begin <= ac && ac <= alpha_end {
             // dbg!(&self.alpha_to_trie_map);
-            return Some(self.alpha_to_trie_map[ac.wrapping_sub(alpha_begin) as usize]);
+            let tc = self.alpha_to_trie_map[ac.wrapping_sub(alpha_begin) as usize];
+            // characters in the gaps between ranges are not part of the map
+            if tc != Self::ERROR_CHAR {
+                return Some(tc);
+            }
         }
         None
     }
@@ -244,42 +369,29 @@ impl AlphaMap {
         }
         !(0 as AlphaChar)
     }
+    /// Number of trie-char codes given to chars, not counting the
+    /// terminator.
+    pub(crate) fn num_symbols(&self) -> usize {
+        self.trie_to_alpha_map.len().saturating_sub(1)
+    }
     pub(crate) fn trie_to_char2(&self, tc: TrieChar) -> Option<AlphaChar> {
         self.trie_to_alpha_map.get(tc as usize).copied()
     }
 
+    /// Code `str` into a trie-char string.
+    ///
+    /// Returns `None` if `str` has a character outside the map, or one
+    /// whose code does not fit in a trie char.
     pub(crate) fn char_to_trie_str(&self, str: &AlphaStr) -> Option<TrieCharString> {
         let mut buf = Vec::with_capacity(str.count_slice() + 1);
-        dbg!(str);
-        let mut str = str.to_slice_with_nul();
-        loop {
-            dbg!(str[0]);
-            if str[0] == 0 {
-                break;
-            }
-            if let Some(tc) = self.char_to_trie(str[0]) {
-                debug_assert_ne!(tc, 0);
-                dbg!(&tc);
-                let tc = tc as TrieChar;
-                dbg!(&tc);
-                // debug_assert_ne!(tc, 0);
-                if tc != 0 {
-                    buf.push(tc as TrieChar);
-                } else {
-                    eprintln!("ignoring tc==0 ...");
-                }
-                str = &str[1..];
-            }
-        }
-        // TODO: use from_vec_unchecked?
-        dbg!(&buf);
-        match TrieCharString::new(buf) {
-            Ok(str) => Some(str),
-            Err(err) => {
-                eprintln!("alpha_map:char_to_trie_str2: failed create TrieCharString: {err:?}");
-                None
-            }
+        for &ac in str.to_slice() {
+            let tc = TrieChar::try_from(self.char_to_trie(ac)?).ok()?;
+            debug_assert!(tc != 0);
+            buf.push(tc);
         }
+        // SAFETY: `buf` holds no 0 bytes, as `str` holds no char 0 and other
+        // chars are coded from 1
+        Some(unsafe { TrieCharString::from_vec_unchecked(buf) })
     }
 }
 
@@ -143,6 +143,24 @@ mod char_to_trie {
         Ok(())
     }
 
+    #[rstest]
+    #[case(0x41, Some(1))]
+    #[case(0x51, None)]
+    #[case(0x60, Some(17))]
+    fn char_to_trie_with_gap_works(
+        #[case] given: AlphaChar,
+        #[case] expected: Option<TrieIndex>,
+    ) -> DatrieResult<()> {
+        let mut alpha_map = AlphaMap::default();
+        alpha_map.add_range(0x41, 0x50)?;
+        alpha_map.add_range(0x60, 0x70)?;
+        let actual = alpha_map.char_to_trie(given);
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
     #[test]
     fn char_to_trie_str() -> DatrieResult<()> {
         let mut alpha_map = AlphaMap::default();
@@ -155,6 +173,23 @@ mod char_to_trie {
 
         Ok(())
     }
+
+    #[test]
+    fn char_to_trie_str_rejects_codes_beyond_trie_char() -> DatrieResult<()> {
+        let mut alpha_map = AlphaMap::default();
+        alpha_map.add_range(0x4e00, 0x4e00 + 299)?;
+        let fits = AlphaStr::from_slice_with_nul(&[0x4e00, 0x4e00 + 254, 0]).unwrap();
+        let too_wide = AlphaStr::from_slice_with_nul(&[0x4e00, 0x4e00 + 255, 0]).unwrap();
+
+        assert_eq!(alpha_map.char_to_trie(0x4e00 + 255), Some(256));
+        assert_eq!(
+            alpha_map.char_to_trie_str(fits).map(|s| s.count_bytes()),
+            Some(2)
+        );
+        assert!(alpha_map.char_to_trie_str(too_wide).is_none());
+
+        Ok(())
+    }
 }
 #[test]
 fn get_total_ranges_works() -> DatrieResult<()> {
@@ -176,3 +211,69 @@ fn serialize_works() -> DatrieResult<()> {
     alpha_map.serialize(&mut serialized_self_data)?;
     Ok(())
 }
+
+mod symbol_order {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn from_ordered_symbols_assigns_codes_in_order() -> DatrieResult<()> {
+        let alpha_map = AlphaMap::from_ordered_symbols(&[0x63, 0x61, 0x62])?;
+        assert_eq!(alpha_map.char_to_trie(0x63), Some(1));
+        assert_eq!(alpha_map.char_to_trie(0x61), Some(2));
+        assert_eq!(alpha_map.char_to_trie(0x62), Some(3));
+        assert_eq!(alpha_map.trie_to_char(1), 0x63);
+        Ok(())
+    }
+
+    #[test]
+    fn unlisted_symbols_follow_in_code_point_order() -> DatrieResult<()> {
+        let mut alpha_map = AlphaMap::default();
+        alpha_map.add_range(0x61, 0x64)?;
+        alpha_map.set_symbol_order(&[0x64])?;
+        assert_eq!(alpha_map.char_to_trie(0x64), Some(1));
+        assert_eq!(alpha_map.char_to_trie(0x61), Some(2));
+        assert_eq!(alpha_map.char_to_trie(0x63), Some(4));
+        Ok(())
+    }
+
+    #[test]
+    fn sort_symbols_by_works() -> DatrieResult<()> {
+        let mut alpha_map = AlphaMap::default();
+        alpha_map.add_range(0x61, 0x63)?;
+        alpha_map.sort_symbols_by(|a, b| b.cmp(a))?;
+        assert_eq!(alpha_map.symbol_order(), &[0x63, 0x62, 0x61]);
+        assert_eq!(alpha_map.char_to_trie(0x63), Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn set_symbol_order_rejects_invalid_orders() -> DatrieResult<()> {
+        let mut alpha_map = AlphaMap::default();
+        alpha_map.add_range(0x61, 0x63)?;
+        assert!(alpha_map.set_symbol_order(&[0x64]).is_err());
+        assert!(alpha_map.set_symbol_order(&[0x61, 0x61]).is_err());
+        assert!(AlphaMap::from_ordered_symbols(&[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn order_survives_serialization() -> DatrieResult<()> {
+        let alpha_map = AlphaMap::from_ordered_symbols(&[0x63, 0x61, 0x62])?;
+        let size = alpha_map.get_serialized_size();
+        assert_eq!(size, 4 + 4 + 8 + 4 + 3 * 4);
+
+        let mut data = Vec::with_capacity(size);
+        alpha_map.serialize(&mut data)?;
+        assert_eq!(data.len(), size);
+        let mut data_from_slice = vec![0; size];
+        assert_eq!(alpha_map.serialize_to_slice(&mut data_from_slice)?, size);
+        assert_eq!(data, data_from_slice);
+
+        let read_map = AlphaMap::fread_bin_safe(&mut Cursor::new(data))?;
+        assert_eq!(read_map.symbol_order(), &[0x63, 0x61, 0x62]);
+        assert_eq!(read_map.char_to_trie(0x63), Some(1));
+        Ok(())
+    }
+}
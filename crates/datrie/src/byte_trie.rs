@@ -0,0 +1,94 @@
+use std::io;
+
+use crate::fileutils::ReadExt;
+use crate::trie::TrieChar;
+use crate::{AlphaMap, DatrieError, DatrieResult, ErrorKind, Trie, TrieData};
+
+/// A trie keyed by raw bytes.
+///
+/// Key bytes are used as trie chars as they are, without a lookup through
+/// an [`AlphaMap`]. Trie char 0 terminates keys, so the bytes 0x00 and 0x01
+/// are escaped as 0x01 0x01 and 0x01 0x02. The escape keeps keys in byte
+/// order.
+///
+/// A `ByteTrie` is saved in the plain trie format, with an alpha map
+/// covering 0x01..=0xff.
+#[derive(Debug)]
+pub struct ByteTrie {
+    trie: Trie,
+}
+
+const ESCAPE: TrieChar = 0x01;
+
+impl ByteTrie {
+    pub fn new() -> DatrieResult<ByteTrie> {
+        Ok(ByteTrie {
+            trie: Trie::new(&Self::alpha_map()?)?,
+        })
+    }
+
+    pub fn fread_safe<R: ReadExt + io::Seek>(reader: &mut R) -> DatrieResult<ByteTrie> {
+        let trie = Trie::fread_safe(reader)?;
+        if trie.alpha_map != Self::alpha_map()? {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "ByteTrie::fread_safe: alpha map is not the byte map 0x01..=0xff".into(),
+            ));
+        }
+        Ok(ByteTrie { trie })
+    }
+
+    /// The identity alpha map that byte tries are stored with.
+    fn alpha_map() -> DatrieResult<AlphaMap> {
+        let mut alpha_map = AlphaMap::default();
+        alpha_map.add_range(0x01, 0xff)?;
+        Ok(alpha_map)
+    }
+}
+
+impl ByteTrie {
+    pub fn get_serialized_size(&self) -> usize {
+        self.trie.get_serialized_size()
+    }
+
+    pub fn serialize_safe(&mut self, writer: impl io::Write) -> DatrieResult<()> {
+        self.trie.serialize_safe(writer)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.trie.is_dirty()
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<TrieData> {
+        self.trie.retrieve_trie_key(&encode_key(key))
+    }
+
+    /// Store `data` for `key`, returning the data it replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the double-array cannot grow to hold the key.
+    pub fn insert(&mut self, key: &[u8], data: TrieData) -> Option<TrieData> {
+        self.trie
+            .insert_trie_key(&encode_key(key), data)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Delete `key`, returning the data that was stored for it.
+    pub fn remove(&mut self, key: &[u8]) -> Option<TrieData> {
+        self.trie.remove_trie_key(&encode_key(key))
+    }
+}
+
+/// Escape `key` into a nul-terminated trie-char key.
+fn encode_key(key: &[u8]) -> Vec<TrieChar> {
+    let mut trie_key = Vec::with_capacity(key.len() + 1);
+    for &b in key {
+        match b {
+            0x00 | ESCAPE => trie_key.extend_from_slice(&[ESCAPE, b + 1]),
+            _ => trie_key.push(b),
+        }
+    }
+    trie_key.push(0);
+    trie_key
+}
@@ -1,3 +1,4 @@
+use std::cell::{Cell, OnceCell};
 use std::io::{self, SeekFrom};
 
 use ::libc;
@@ -6,7 +7,7 @@ use core::mem::size_of;
 
 use crate::{
     fileutils::{file_write_int32, serialize_int32_be_incr, CFile, ReadExt},
-    trie::TrieIndex,
+    trie::{TrieData, TrieIndex},
     trie_string::{trie_string_append_char, trie_string_cut_last, TrieString},
     DatrieError, DatrieResult, ErrorKind,
 };
@@ -38,6 +39,16 @@ pub struct DArray {
     pub num_cells: TrieIndex,
     pub cells: *mut DACell,
     pub cells2: Vec<DACell>,
+    /// Number of keys below each cell, if key counting is on.
+    counts: Option<Vec<u32>>,
+    /// Separate node of each tail block, indexed by block. Built on the
+    /// first lookup, then kept current as separate nodes are set and
+    /// moved; entries of freed nodes are left stale.
+    tail_nodes: OnceCell<Vec<TrieIndex>>,
+    /// Largest data below each cell, built on the first top-k query, then
+    /// kept current as keys are stored and deleted. Free cells hold
+    /// `TrieData::MIN`.
+    max_data: OnceCell<Vec<Cell<TrieData>>>,
 }
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
@@ -128,6 +139,9 @@ impl DArray {
             num_cells,
             cells,
             cells2,
+            counts: None,
+            tail_nodes: OnceCell::new(),
+            max_data: OnceCell::new(),
         })
     }
 }
@@ -213,6 +227,9 @@ impl DArray {
                         num_cells,
                         cells,
                         cells2,
+                        counts: None,
+                        tail_nodes: OnceCell::new(),
+                        max_data: OnceCell::new(),
                     })
                 }
                 _ => unsafe {
@@ -315,6 +332,9 @@ impl DArray {
                     num_cells,
                     cells,
                     cells2,
+                    counts: None,
+                    tail_nodes: OnceCell::new(),
+                    max_data: OnceCell::new(),
                 })
             }
             _ => unsafe {
@@ -582,6 +602,15 @@ impl DArray {
             self.alloc_cell(new_next);
             self.set_check(new_next, s);
             self.set_base(new_next, old_next_base);
+            if let Some(counts) = self.counts.as_mut() {
+                counts[new_next as usize] = counts[old_next as usize];
+            }
+            if let Some(max_data) = self.max_data.get_mut() {
+                max_data[new_next as usize] = max_data[old_next as usize].clone();
+            }
+            if old_next_base < 0 {
+                self.note_tail_node(-old_next_base, new_next);
+            }
             if old_next_base > 0 as libc::c_int {
                 let mut c: TrieIndex;
                 let max_c: TrieIndex = if (255 as libc::c_int) < self.num_cells - old_next_base {
@@ -632,6 +661,12 @@ impl DArray {
             self.cells2.push(DACell { base: 0, check: 0 });
         }
         assert_eq!(self.num_cells as usize, self.cells2.len());
+        if let Some(counts) = self.counts.as_mut() {
+            counts.resize(self.cells2.len(), 0);
+        }
+        if let Some(max_data) = self.max_data.get_mut() {
+            max_data.resize(self.cells2.len(), Cell::new(TrieData::MIN));
+        }
 
         i = new_begin;
         while i < to_index {
@@ -670,19 +705,234 @@ impl DArray {
         self.set_base(next, -prev);
     }
     fn free_cell(&mut self, cell: TrieIndex) {
-        // let mut i: TrieIndex = 0;
-        // let mut prev: TrieIndex = 0;
+        self.free_cells(&[cell]);
+    }
+
+    /// Return `cells`, sorted ascending, to the free list in one pass.
+    fn free_cells(&mut self, cells: &[TrieIndex]) {
         let mut i = -self.get_check(1 as libc::c_int);
-        while i != 1 as libc::c_int && i < cell {
-            i = -self.get_check(i);
+        for &cell in cells {
+            while i != 1 as libc::c_int && i < cell {
+                i = -self.get_check(i);
+            }
+            let prev = -self.get_base(i);
+            self.set_check(cell, -i);
+            self.set_base(cell, -prev);
+            self.set_check(prev, -cell);
+            self.set_base(i, -cell);
+            if let Some(counts) = self.counts.as_mut() {
+                counts[cell as usize] = 0;
+            }
+            if let Some(max_data) = self.max_data.get_mut() {
+                max_data[cell as usize].set(TrieData::MIN);
+            }
         }
-        let prev = -self.get_base(i);
-        self.set_check(cell, -i);
-        self.set_base(cell, -prev);
-        self.set_check(prev, -cell);
-        self.set_base(i, -cell);
+    }
+
+    /// Free node `s` and all nodes below it, then prune the path above.
+    ///
+    /// The root itself is kept, losing only its children. Returns the tail
+    /// blocks of the separate nodes freed.
+    pub(crate) fn remove_subtree(&mut self, s: TrieIndex) -> Vec<TrieIndex> {
+        let root = self.get_root();
+        let mut cells = Vec::new();
+        let mut tails = Vec::new();
+        let mut stack = vec![s];
+        while let Some(node) = stack.pop() {
+            let base = self.get_base(node);
+            if base < 0 {
+                tails.push(-base);
+            } else if base > 0 {
+                let syms = self.output_symbols(node);
+                stack.extend((0..syms.num()).map(|i| base + syms.get(i) as TrieIndex));
+            }
+            if node != root {
+                cells.push(node);
+            }
+        }
+        self.add_count(s, None, -(tails.len() as i32));
+        self.set_max_data(s, TrieData::MIN);
+        let parent = self.get_check(s);
+        cells.sort_unstable();
+        self.free_cells(&cells);
+        if s != root {
+            self.prune(parent);
+        }
+        tails
     }
 }
+
+impl DArray {
+    /// Start or stop keeping the number of keys below each node.
+    ///
+    /// Starting counts the keys below every node once; afterwards
+    /// [`add_count`](Self::add_count) keeps them current.
+    pub(crate) fn set_counting(&mut self, enabled: bool) {
+        if !enabled {
+            self.counts = None;
+        } else if self.counts.is_none() {
+            let mut counts = vec![0; self.num_cells()];
+            self.fill_counts(self.get_root(), &mut counts);
+            self.counts = Some(counts);
+        }
+    }
+
+    fn fill_counts(&self, s: TrieIndex, counts: &mut [u32]) -> u32 {
+        let base = self.get_base(s);
+        let count = if base < 0 {
+            1
+        } else if base > 0 {
+            let syms = self.output_symbols(s);
+            (0..syms.num())
+                .map(|i| self.fill_counts(base + syms.get(i) as TrieIndex, counts))
+                .sum()
+        } else {
+            0
+        };
+        counts[s as usize] = count;
+        count
+    }
+
+    /// Number of keys below node `s`, if key counting is on.
+    pub(crate) fn count(&self, s: TrieIndex) -> Option<usize> {
+        self.counts
+            .as_ref()
+            .map(|counts| counts[s as usize] as usize)
+    }
+
+    /// Add `delta` to the key count of `s` and of its ancestors, stopping
+    /// before ancestor `stop`, or after the root if `stop` is `None`.
+    pub(crate) fn add_count(&mut self, mut s: TrieIndex, stop: Option<TrieIndex>, delta: i32) {
+        if self.counts.is_none() {
+            return;
+        }
+        let root = self.get_root();
+        while Some(s) != stop {
+            let parent = self.get_check(s);
+            let counts = self.counts.as_mut().unwrap();
+            counts[s as usize] = counts[s as usize].wrapping_add_signed(delta);
+            if s == root {
+                break;
+            }
+            s = parent;
+        }
+    }
+}
+impl DArray {
+    /// The largest data below each cell, indexed by cell.
+    ///
+    /// The first call walks the whole double-array, taking the data of the
+    /// key in each tail block from `data_of`; afterwards
+    /// [`set_max_data`](Self::set_max_data) keeps the maxima current.
+    pub(crate) fn max_data<F>(&self, data_of: F) -> &[Cell<TrieData>]
+    where
+        F: Fn(TrieIndex) -> TrieData,
+    {
+        self.max_data.get_or_init(|| {
+            let max_data = vec![Cell::new(TrieData::MIN); self.num_cells()];
+            self.fill_max_data(self.get_root(), &data_of, &max_data);
+            max_data
+        })
+    }
+
+    fn fill_max_data<F>(&self, s: TrieIndex, data_of: &F, max_data: &[Cell<TrieData>]) -> TrieData
+    where
+        F: Fn(TrieIndex) -> TrieData,
+    {
+        let base = self.get_base(s);
+        let max = if base < 0 {
+            data_of(-base)
+        } else if base > 0 {
+            let syms = self.output_symbols(s);
+            (0..syms.num())
+                .map(|i| self.fill_max_data(base + syms.get(i) as TrieIndex, data_of, max_data))
+                .max()
+                .unwrap_or(TrieData::MIN)
+        } else {
+            TrieData::MIN
+        };
+        max_data[s as usize].set(max);
+        max
+    }
+
+    /// Whether the maxima of [`max_data`](Self::max_data) are built.
+    pub(crate) fn has_max_data(&self) -> bool {
+        self.max_data.get().is_some()
+    }
+
+    /// Set the largest data below `s` to `data`, and update its ancestors,
+    /// if the maxima are built.
+    ///
+    /// `s` is a separate node whose data changed, or a node that lost its
+    /// keys, with `data` `TrieData::MIN`.
+    pub(crate) fn set_max_data(&self, mut s: TrieIndex, data: TrieData) {
+        let Some(max_data) = self.max_data.get() else {
+            return;
+        };
+        max_data[s as usize].set(data);
+        let root = self.get_root();
+        while s != root {
+            s = self.get_check(s);
+            let base = self.get_base(s);
+            let syms = self.output_symbols(s);
+            let max = (0..syms.num())
+                .map(|i| max_data[(base + syms.get(i) as TrieIndex) as usize].get())
+                .max()
+                .unwrap_or(TrieData::MIN);
+            // the ancestors above an unchanged node are unchanged too
+            if max_data[s as usize].replace(max) == max {
+                break;
+            }
+        }
+    }
+}
+
+impl DArray {
+    /// Make `s` a separate node, whose key ends in tail block `tail`.
+    pub(crate) fn set_tail_node(&mut self, s: TrieIndex, tail: TrieIndex) {
+        self.set_base(s, -tail);
+        self.note_tail_node(tail, s);
+    }
+
+    fn note_tail_node(&mut self, tail: TrieIndex, s: TrieIndex) {
+        if let Some(tail_nodes) = self.tail_nodes.get_mut() {
+            let t = tail as usize;
+            if tail_nodes.len() <= t {
+                tail_nodes.resize(t + 1, 0);
+            }
+            tail_nodes[t] = s;
+        }
+    }
+
+    /// The separate node whose key ends in tail block `tail`, if any.
+    pub(crate) fn tail_node(&self, tail: TrieIndex) -> Option<TrieIndex> {
+        let tail_nodes = self.tail_nodes.get_or_init(|| self.collect_tail_nodes());
+        let s = *tail_nodes.get(usize::try_from(tail).ok()?)?;
+        // the entry is stale if the node was freed or reused since
+        let live = s > 0 && s < self.num_cells && self.get_check(s) > 0;
+        (live && self.get_base(s) == -tail).then_some(s)
+    }
+
+    fn collect_tail_nodes(&self) -> Vec<TrieIndex> {
+        let mut tail_nodes = Vec::new();
+        let mut stack = vec![self.get_root()];
+        while let Some(s) = stack.pop() {
+            let base = self.get_base(s);
+            if base < 0 {
+                let t = -base as usize;
+                if tail_nodes.len() <= t {
+                    tail_nodes.resize(t + 1, 0);
+                }
+                tail_nodes[t] = s;
+            } else if base > 0 {
+                let syms = self.output_symbols(s);
+                stack.extend((0..syms.num()).map(|i| base + syms.get(i) as TrieIndex));
+            }
+        }
+        tail_nodes
+    }
+}
+
 impl DArray {
     pub unsafe fn first_separate(
         &self,
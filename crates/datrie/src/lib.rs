@@ -7,20 +7,35 @@
 #![allow(clippy::needless_late_init)]
 #![allow(clippy::len_without_is_empty)]
 #![allow(clippy::comparison_chain)]
+mod aho_corasick;
 pub(crate) mod alpha_map;
+mod byte_trie;
 mod darray;
 mod dstring;
 mod error;
 pub mod fileutils;
+mod multimap;
+pub mod segment;
+mod set;
+mod suffix_index;
 mod trie;
 // pub mod trie_char_string;
 pub mod alpha_str;
 pub mod trie_str;
 mod trie_string;
 
+pub use crate::aho_corasick::{AhoCorasick, MatchKind, Matches, TextMatch};
 pub use crate::alpha_str::AlphaStr;
+pub use crate::byte_trie::ByteTrie;
 pub use crate::error::{DatrieError, ErrorKind};
+pub use crate::multimap::TrieMultiMap;
+pub use crate::set::{Keys, TrieSet};
+pub use crate::suffix_index::SuffixIndex;
 pub use alpha_map::{alpha_char_strcmp, AlphaChar, AlphaMap};
 pub use alpha_map::{Bool, DA_FALSE, DA_TRUE};
+pub use trie::{
+    Automaton, Diff, DiffEntry, EditDistance, Entry, EntryId, FuzzyMatch, Iter, OccupiedEntry,
+    Pattern, Range, ResumeToken, Search, VacantEntry,
+};
 pub use trie::{Trie, TrieChar, TrieData, TrieEnumFunc, TrieIndex, TrieIterator, TrieState};
 pub type DatrieResult<T> = Result<T, DatrieError>;
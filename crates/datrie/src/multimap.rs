@@ -0,0 +1,204 @@
+use std::io;
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use crate::darray::DArray;
+use crate::fileutils::ReadExt;
+use crate::trie::{Entry, Tail};
+use crate::{AlphaMap, AlphaStr, DatrieError, DatrieResult, ErrorKind, Trie, TrieData};
+
+/// A trie that maps each key to a list of values.
+///
+/// The values of a key are kept in insertion order, without duplicates.
+/// The data of each key in the inner trie indexes its list.
+///
+/// A `TrieMultiMap` is saved in the plain trie format, except that each
+/// tail block holds a value count and the values in place of the data.
+/// A key read with no values keeps no list, and stays without values.
+#[derive(Debug)]
+pub struct TrieMultiMap {
+    trie: Trie,
+    lists: Vec<Vec<TrieData>>,
+    /// Indices of unused entries of `lists`.
+    free_lists: Vec<usize>,
+}
+
+impl TrieMultiMap {
+    /// Magic number signature for the multimap tail format (0xdffddffd)
+    /// Same layout as the plain tail, with a value count and the values in
+    /// place of the data of each block
+    const TAIL_SIGNATURE: u32 = 0xdffddffd;
+
+    pub fn new(alpha_map: &AlphaMap) -> DatrieResult<TrieMultiMap> {
+        Ok(TrieMultiMap {
+            trie: Trie::new(alpha_map)?,
+            lists: Vec::new(),
+            free_lists: Vec::new(),
+        })
+    }
+
+    pub fn fread_safe<R: ReadExt + io::Seek>(reader: &mut R) -> DatrieResult<TrieMultiMap> {
+        let alpha_map = AlphaMap::fread_bin_safe(reader)?;
+        let da = Box::new(DArray::fread_safe(reader)?);
+        let mut lists = Vec::new();
+        let tail = Tail::fread_with(reader, Self::TAIL_SIGNATURE, |reader| {
+            let mut count = 0;
+            reader.read_int32(&mut count)?;
+            if count < 0 {
+                return Err(DatrieError::new(
+                    ErrorKind::Bug,
+                    "failed to read multimap tail: negative value count".into(),
+                ));
+            }
+            // the count is not trusted for preallocation, a short stream
+            // fails on reading instead
+            let mut values = Vec::new();
+            for _ in 0..count {
+                let mut value = 0;
+                reader.read_int32(&mut value)?;
+                values.push(value);
+            }
+            // free blocks have no values, and keep no list
+            if values.is_empty() {
+                return Ok(-1);
+            }
+            lists.push(values);
+            Ok(lists.len() as TrieData - 1)
+        })?;
+        Ok(TrieMultiMap {
+            trie: Trie::from_parts(alpha_map, da, tail),
+            lists,
+            free_lists: Vec::new(),
+        })
+    }
+}
+
+impl TrieMultiMap {
+    pub fn get_serialized_size(&self) -> usize {
+        self.trie.alpha_map.get_serialized_size()
+            + self.trie.da.get_serialized_size()
+            + self
+                .trie
+                .tail
+                .serialized_size_with(|data| 4 + 4 * self.list(data).len())
+    }
+
+    pub fn serialize_safe(&mut self, mut writer: impl io::Write) -> DatrieResult<()> {
+        self.trie.alpha_map.serialize(&mut writer)?;
+        self.trie.da.serialize(&mut writer)?;
+        let lists = &self.lists;
+        self.trie
+            .tail
+            .serialize_with(&mut writer, Self::TAIL_SIGNATURE, |writer, data| {
+                let values = usize::try_from(data).map_or(&[][..], |i| &lists[i]);
+                writer.write_i32::<BigEndian>(values.len() as i32)?;
+                for &value in values {
+                    writer.write_i32::<BigEndian>(value)?;
+                }
+                Ok(4 + 4 * values.len())
+            })?;
+        self.trie.is_dirty = false;
+        Ok(())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.trie.is_dirty()
+    }
+
+    /// The values of `key`, empty if it is not in the map.
+    pub fn values(&self, key: &AlphaStr) -> &[TrieData] {
+        self.trie.get(key).map_or(&[], |data| self.list(data))
+    }
+
+    pub fn contains_key(&self, key: &AlphaStr) -> bool {
+        self.trie.contains_key(key)
+    }
+
+    /// Number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Add `value` to the values of `key`, returning whether it was new.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`](crate::ErrorKind) if `key`
+    /// has characters outside the alpha map, and with
+    /// [`ErrorKind::Memory`](crate::ErrorKind) if the double-array cannot
+    /// grow to hold it.
+    pub fn insert_value(&mut self, key: &AlphaStr, value: TrieData) -> DatrieResult<bool> {
+        let inserted = match self.trie.entry(key)? {
+            Entry::Occupied(entry) => {
+                let Some(values) = usize::try_from(entry.get())
+                    .ok()
+                    .and_then(|i| self.lists.get_mut(i))
+                else {
+                    // a key without a list takes no values
+                    return Ok(false);
+                };
+                if values.contains(&value) {
+                    return Ok(false);
+                }
+                values.push(value);
+                // the trie is unchanged, but the saved map is not
+                self.trie.is_dirty = true;
+                true
+            }
+            Entry::Vacant(entry) => {
+                let i = self.free_lists.last().copied().unwrap_or(self.lists.len());
+                entry.try_insert(i as TrieData)?;
+                if self.free_lists.pop().is_some() {
+                    self.lists[i].push(value);
+                } else {
+                    self.lists.push(vec![value]);
+                }
+                true
+            }
+        };
+        Ok(inserted)
+    }
+
+    /// Remove `value` from the values of `key`, returning whether it was
+    /// there.
+    ///
+    /// A key whose last value is removed leaves the map.
+    pub fn remove_value(&mut self, key: &AlphaStr, value: TrieData) -> bool {
+        let Some(data) = self.trie.get(key) else {
+            return false;
+        };
+        let Ok(i) = usize::try_from(data) else {
+            return false;
+        };
+        let Some(pos) = self
+            .lists
+            .get(i)
+            .and_then(|list| list.iter().position(|&v| v == value))
+        else {
+            return false;
+        };
+        self.lists[i].remove(pos);
+        if self.lists[i].is_empty() {
+            self.trie.remove(key);
+            self.free_lists.push(i);
+        } else {
+            self.trie.is_dirty = true;
+        }
+        true
+    }
+
+    /// Remove `key` with all its values, returning the values.
+    ///
+    /// Gives `None` if `key` is not in the map, or has no values.
+    pub fn remove(&mut self, key: &AlphaStr) -> Option<Vec<TrieData>> {
+        let i = usize::try_from(self.trie.remove(key)?).ok()?;
+        self.free_lists.push(i);
+        Some(std::mem::take(&mut self.lists[i]))
+    }
+
+    fn list(&self, data: TrieData) -> &[TrieData] {
+        usize::try_from(data).map_or(&[], |i| &self.lists[i])
+    }
+}
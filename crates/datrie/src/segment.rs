@@ -0,0 +1,199 @@
+//! Dictionary-based word segmentation.
+//!
+//! A [`Segmenter`] splits text into the words of a [`Trie`], walking a
+//! [`TrieState`] from each candidate word start. Text that no word covers
+//! is split into unknown segments as the [`Fallback`] says.
+//!
+//! For scoring segmentations elsewhere, [`Trie::build_lattice`] lists every
+//! word at every position as a [`Lattice`].
+
+use crate::{AlphaChar, Trie, TrieData, TrieState};
+
+pub use self::lattice::{BestPaths, Edge, Lattice, Path};
+
+mod lattice;
+
+/// A segment of the input text.
+///
+/// Offsets are in chars for `&[AlphaChar]` input and in bytes for `&str`
+/// input, with `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: usize,
+    pub end: usize,
+    /// Data of the dictionary word, or `None` for an unknown span.
+    pub data: Option<TrieData>,
+}
+
+/// How text that starts no dictionary word is segmented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fallback {
+    /// Each unknown char is a segment of its own.
+    #[default]
+    SingleChar,
+    /// A run of unknown chars is one segment.
+    MergeRun,
+}
+
+/// Segments text into the words of a trie.
+#[derive(Debug, Clone, Copy)]
+pub struct Segmenter<'a> {
+    trie: &'a Trie,
+    fallback: Fallback,
+}
+
+impl<'a> Segmenter<'a> {
+    pub fn new(trie: &'a Trie) -> Segmenter<'a> {
+        Segmenter {
+            trie,
+            fallback: Fallback::default(),
+        }
+    }
+
+    pub fn with_fallback(mut self, fallback: Fallback) -> Segmenter<'a> {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Greedy maximal matching: take the longest word at each position.
+    pub fn greedy(&self, text: &[AlphaChar]) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let mut start = 0;
+        while start < text.len() {
+            let mut longest = None;
+            words_at(self.trie, text, start, |end, data| {
+                longest = Some((end, data))
+            });
+            let segment = match longest {
+                Some((end, data)) => Segment {
+                    start,
+                    end,
+                    data: Some(data),
+                },
+                None => Segment {
+                    start,
+                    end: start + 1,
+                    data: None,
+                },
+            };
+            start = segment.end;
+            segments.push(segment);
+        }
+        self.apply_fallback(segments)
+    }
+
+    /// Maximal matching with the fewest words.
+    ///
+    /// Among all segmentations, the one with the fewest unknown chars is
+    /// chosen, and then the one with the fewest segments.
+    pub fn min_words(&self, text: &[AlphaChar]) -> Vec<Segment> {
+        // best[i] is the cost of segmenting `text[..i]`, as (unknown chars,
+        // segments), with the segment that ends at `i`
+        let mut best: Vec<Option<((usize, usize), Segment)>> = vec![None; text.len() + 1];
+        for start in 0..text.len() {
+            // every position can be reached through unknown chars
+            let (unknown, count) = best[start].map_or((0, 0), |(cost, _)| cost);
+            let mut relax = |segment: Segment, cost: (usize, usize)| {
+                let end = segment.end;
+                if best[end].map_or(true, |(c, _)| cost < c) {
+                    best[end] = Some((cost, segment));
+                }
+            };
+            relax(
+                Segment {
+                    start,
+                    end: start + 1,
+                    data: None,
+                },
+                (unknown + 1, count + 1),
+            );
+            words_at(self.trie, text, start, |end, data| {
+                relax(
+                    Segment {
+                        start,
+                        end,
+                        data: Some(data),
+                    },
+                    (unknown, count + 1),
+                )
+            });
+        }
+        let mut segments = Vec::new();
+        let mut end = text.len();
+        while end > 0 {
+            let (_, segment) = best[end].unwrap();
+            segments.push(segment);
+            end = segment.start;
+        }
+        segments.reverse();
+        self.apply_fallback(segments)
+    }
+
+    /// [`greedy`](Self::greedy) over a string, with byte offsets.
+    pub fn greedy_str(&self, text: &str) -> Vec<Segment> {
+        let (chars, offsets) = decode_str(text);
+        to_byte_offsets(self.greedy(&chars), &offsets)
+    }
+
+    /// [`min_words`](Self::min_words) over a string, with byte offsets.
+    pub fn min_words_str(&self, text: &str) -> Vec<Segment> {
+        let (chars, offsets) = decode_str(text);
+        to_byte_offsets(self.min_words(&chars), &offsets)
+    }
+
+    fn apply_fallback(&self, segments: Vec<Segment>) -> Vec<Segment> {
+        if self.fallback == Fallback::SingleChar {
+            return segments;
+        }
+        let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+        for segment in segments {
+            match merged.last_mut() {
+                Some(last) if last.data.is_none() && segment.data.is_none() => {
+                    last.end = segment.end;
+                }
+                _ => merged.push(segment),
+            }
+        }
+        merged
+    }
+}
+
+/// Call `f` with the end and data of every word of `trie` that starts at
+/// `text[start]`, shortest first.
+fn words_at(trie: &Trie, text: &[AlphaChar], start: usize, mut f: impl FnMut(usize, TrieData)) {
+    let mut state = TrieState::at_root(trie);
+    for (end, &ac) in text.iter().enumerate().skip(start) {
+        if ac == 0 {
+            return;
+        }
+        // SAFETY: `state` points into `trie`, which outlives it
+        unsafe {
+            if TrieState::walk(&mut state, ac) == 0 {
+                return;
+            }
+            if TrieState::is_terminal(&state) {
+                f(end + 1, TrieState::get_data(&state));
+            }
+        }
+    }
+}
+
+/// The chars of `text`, and the byte offset of each char plus the end.
+fn decode_str(text: &str) -> (Vec<AlphaChar>, Vec<usize>) {
+    let mut chars = Vec::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    for (offset, c) in text.char_indices() {
+        chars.push(c as AlphaChar);
+        offsets.push(offset);
+    }
+    offsets.push(text.len());
+    (chars, offsets)
+}
+
+fn to_byte_offsets(mut segments: Vec<Segment>, offsets: &[usize]) -> Vec<Segment> {
+    for segment in &mut segments {
+        segment.start = offsets[segment.start];
+        segment.end = offsets[segment.end];
+    }
+    segments
+}
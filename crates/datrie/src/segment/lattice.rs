@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::words_at;
+use crate::{AlphaChar, Trie, TrieData};
+
+/// A dictionary word found in the text, from char `start` to `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub start: usize,
+    pub end: usize,
+    pub data: TrieData,
+}
+
+/// Every dictionary word at every position of a text, as a DAG over the
+/// char positions `0..=text_len`. See [`Trie::build_lattice`].
+#[derive(Debug, Clone)]
+pub struct Lattice {
+    text_len: usize,
+    /// Edges sorted by start, then end.
+    edges: Vec<Edge>,
+    /// Edges starting at `i` are `edges[edges_from[i]..edges_from[i + 1]]`.
+    edges_from: Vec<usize>,
+    /// Indices into `edges`, sorted by end.
+    by_end: Vec<usize>,
+    /// Edges ending at `i` are `by_end[edges_to[i]..edges_to[i + 1]]`.
+    edges_to: Vec<usize>,
+}
+
+impl Trie {
+    /// Build the lattice of all words of this trie in `text`.
+    ///
+    /// One trie walker is run from each start position, reporting every
+    /// word it passes.
+    pub fn build_lattice(&self, text: &[AlphaChar]) -> Lattice {
+        let mut edges = Vec::new();
+        let mut edges_from = Vec::with_capacity(text.len() + 2);
+        for start in 0..text.len() {
+            edges_from.push(edges.len());
+            words_at(self, text, start, |end, data| {
+                edges.push(Edge { start, end, data })
+            });
+        }
+        edges_from.resize(text.len() + 2, edges.len());
+
+        let mut by_end: Vec<usize> = (0..edges.len()).collect();
+        by_end.sort_by_key(|&i| (edges[i].end, edges[i].start));
+        let mut edges_to = Vec::with_capacity(text.len() + 2);
+        let mut i = 0;
+        for pos in 0..=text.len() {
+            while i < by_end.len() && edges[by_end[i]].end < pos {
+                i += 1;
+            }
+            edges_to.push(i);
+        }
+        edges_to.push(by_end.len());
+
+        Lattice {
+            text_len: text.len(),
+            edges,
+            edges_from,
+            by_end,
+            edges_to,
+        }
+    }
+}
+
+impl Lattice {
+    /// Length of the text, which is the last position of the lattice.
+    pub fn text_len(&self) -> usize {
+        self.text_len
+    }
+
+    /// All edges, sorted by start and then end.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Edges that start at `pos`, shortest first.
+    pub fn edges_from(&self, pos: usize) -> &[Edge] {
+        if pos > self.text_len {
+            return &[];
+        }
+        &self.edges[self.edges_from[pos]..self.edges_from[pos + 1]]
+    }
+
+    /// Edges that end at `pos`, longest first.
+    pub fn edges_to(&self, pos: usize) -> impl Iterator<Item = &Edge> + '_ {
+        let range = if pos > self.text_len {
+            0..0
+        } else {
+            self.edges_to[pos]..self.edges_to[pos + 1]
+        };
+        self.by_end[range].iter().map(move |&i| &self.edges[i])
+    }
+
+    /// Paths of edges from position 0 to the end of the text, cheapest
+    /// first, where a path costs the sum of `cost` over its edges.
+    ///
+    /// `cost` is called once per edge. Text that no word covers breaks all
+    /// paths, so the iterator is then empty.
+    pub fn best_paths<F: FnMut(&Edge) -> f64>(&self, mut cost: F) -> BestPaths<'_> {
+        let costs: Vec<f64> = self.edges.iter().map(&mut cost).collect();
+        // rest[i] is the cost of the cheapest path from `i` to the end
+        let mut rest = vec![f64::INFINITY; self.text_len + 1];
+        rest[self.text_len] = 0.0;
+        for pos in (0..self.text_len).rev() {
+            for i in self.edges_from[pos]..self.edges_from[pos + 1] {
+                rest[pos] = rest[pos].min(costs[i] + rest[self.edges[i].end]);
+            }
+        }
+        let mut heap = BinaryHeap::new();
+        if rest[0].is_finite() {
+            heap.push(PartialPath {
+                bound: rest[0],
+                cost: 0.0,
+                pos: 0,
+                last: None,
+            });
+        }
+        BestPaths {
+            lattice: self,
+            costs,
+            rest,
+            heap,
+            links: Vec::new(),
+        }
+    }
+}
+
+/// A full path through a [`Lattice`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub edges: Vec<Edge>,
+    pub cost: f64,
+}
+
+/// Iterator over the paths of a [`Lattice`], see [`Lattice::best_paths`].
+pub struct BestPaths<'a> {
+    lattice: &'a Lattice,
+    costs: Vec<f64>,
+    rest: Vec<f64>,
+    heap: BinaryHeap<PartialPath>,
+    /// Shared path prefixes, as (edge index, previous link).
+    links: Vec<(usize, Option<usize>)>,
+}
+
+/// A path from position 0 to `pos`, ordered by the cost of its cheapest
+/// completion.
+struct PartialPath {
+    bound: f64,
+    cost: f64,
+    pos: usize,
+    last: Option<usize>,
+}
+
+impl PartialEq for PartialPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PartialPath {}
+
+impl PartialOrd for PartialPath {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PartialPath {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed, so the heap pops the cheapest path first
+        other.bound.total_cmp(&self.bound)
+    }
+}
+
+impl Iterator for BestPaths<'_> {
+    type Item = Path;
+
+    fn next(&mut self) -> Option<Path> {
+        while let Some(path) = self.heap.pop() {
+            if path.pos == self.lattice.text_len {
+                let mut edges = Vec::new();
+                let mut link = path.last;
+                while let Some(i) = link {
+                    let (edge, prev) = self.links[i];
+                    edges.push(self.lattice.edges[edge]);
+                    link = prev;
+                }
+                edges.reverse();
+                return Some(Path {
+                    edges,
+                    cost: path.cost,
+                });
+            }
+            let lattice = self.lattice;
+            for i in lattice.edges_from[path.pos]..lattice.edges_from[path.pos + 1] {
+                let end = lattice.edges[i].end;
+                if !self.rest[end].is_finite() {
+                    continue;
+                }
+                let cost = path.cost + self.costs[i];
+                self.links.push((i, path.last));
+                self.heap.push(PartialPath {
+                    bound: cost + self.rest[end],
+                    cost,
+                    pos: end,
+                    last: Some(self.links.len() - 1),
+                });
+            }
+        }
+        None
+    }
+}
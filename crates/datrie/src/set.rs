@@ -0,0 +1,220 @@
+use std::io;
+
+use crate::darray::DArray;
+use crate::fileutils::ReadExt;
+use crate::trie::{Entry, Iter, Tail};
+use crate::{AlphaChar, AlphaMap, AlphaStr, DatrieResult, Trie, TrieChar, TrieData};
+
+/// A set of keys, with no data stored for them.
+///
+/// A `TrieSet` is saved in the plain trie format, except that tail blocks
+/// leave out the data field.
+#[derive(Debug)]
+pub struct TrieSet {
+    trie: Trie,
+}
+
+/// Iterator over the keys of a [`TrieSet`] in order.
+pub struct Keys<'a> {
+    iter: Iter<'a>,
+}
+
+/// Data stored for the keys of the inner trie.
+const NO_DATA: TrieData = -1;
+
+impl TrieSet {
+    /// Magic number signature for the set tail format (0xdffedffe)
+    /// Same layout as the plain tail, without the data of each block
+    const TAIL_SIGNATURE: u32 = 0xdffedffe;
+
+    pub fn new(alpha_map: &AlphaMap) -> DatrieResult<TrieSet> {
+        Ok(TrieSet {
+            trie: Trie::new(alpha_map)?,
+        })
+    }
+
+    pub fn fread_safe<R: ReadExt + io::Seek>(reader: &mut R) -> DatrieResult<TrieSet> {
+        let alpha_map = AlphaMap::fread_bin_safe(reader)?;
+        let da = Box::new(DArray::fread_safe(reader)?);
+        let tail = Tail::fread_with(reader, Self::TAIL_SIGNATURE, |_| Ok(NO_DATA))?;
+        Ok(TrieSet {
+            trie: Trie::from_parts(alpha_map, da, tail),
+        })
+    }
+
+    pub fn alpha_map(&self) -> &AlphaMap {
+        &self.trie.alpha_map
+    }
+}
+
+impl TrieSet {
+    pub fn get_serialized_size(&self) -> usize {
+        self.trie.alpha_map.get_serialized_size()
+            + self.trie.da.get_serialized_size()
+            + self.trie.tail.serialized_size_with(|_| 0)
+    }
+
+    pub fn serialize_safe(&mut self, mut writer: impl io::Write) -> DatrieResult<()> {
+        self.trie.alpha_map.serialize(&mut writer)?;
+        self.trie.da.serialize(&mut writer)?;
+        self.trie
+            .tail
+            .serialize_with(&mut writer, Self::TAIL_SIGNATURE, |_, _| Ok(0))?;
+        self.trie.is_dirty = false;
+        Ok(())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.trie.is_dirty()
+    }
+
+    /// Add `key`, returning whether it was new.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`](crate::ErrorKind) if `key`
+    /// has characters outside the alpha map, and with
+    /// [`ErrorKind::Memory`](crate::ErrorKind) if the double-array cannot
+    /// grow to hold it.
+    pub fn insert(&mut self, key: &AlphaStr) -> DatrieResult<bool> {
+        Ok(match self.trie.entry(key)? {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.try_insert(NO_DATA)?;
+                true
+            }
+        })
+    }
+
+    pub fn contains(&self, key: &AlphaStr) -> bool {
+        self.trie.contains_key(key)
+    }
+
+    /// Remove `key`, returning whether it was in the set.
+    pub fn remove(&mut self, key: &AlphaStr) -> bool {
+        self.trie.remove(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Iterate over the keys in order.
+    pub fn iter(&self) -> Keys<'_> {
+        Keys {
+            iter: self.trie.iter(),
+        }
+    }
+
+    /// Iterate over the keys that start with `prefix`, in order.
+    pub fn iter_prefix(&self, prefix: &AlphaStr) -> Keys<'_> {
+        Keys {
+            iter: self.trie.iter_prefix(prefix),
+        }
+    }
+
+    /// Add `keys`, first widening the alpha map once to cover all their
+    /// characters.
+    fn insert_widening(&mut self, keys: &[Vec<AlphaChar>]) {
+        let alpha_map = &self.trie.alpha_map;
+        let mut missing: Vec<AlphaChar> = keys
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|&ac| alpha_map.char_to_trie(ac).is_none())
+            .collect();
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            missing.dedup();
+            // trie char 0 is the terminator, which leaves TrieChar::MAX
+            // codes for symbols
+            assert!(
+                alpha_map.num_symbols() + missing.len() <= TrieChar::MAX as usize,
+                "TrieSet: keys have more than {} distinct chars",
+                TrieChar::MAX
+            );
+            let mut alpha_map = alpha_map.clone();
+            for ac in missing {
+                alpha_map
+                    .add_range(ac, ac)
+                    .expect("TrieSet: cannot widen the alpha map");
+            }
+            self.trie = self
+                .trie
+                .remap_alphabet(&alpha_map)
+                .expect("TrieSet: cannot remap to a wider alpha map");
+        }
+        for key in keys {
+            let mut key = key.clone();
+            key.push(0);
+            self.insert(AlphaStr::from_slice_with_nul(&key).unwrap())
+                .expect("TrieSet: key outside the widened alpha map");
+        }
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for TrieSet {
+    /// Add the keys, widening the alpha map to cover their characters.
+    ///
+    /// The alpha map is widened once for all the keys, before any is
+    /// added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key contains a nul char, or if the set would have more
+    /// than 255 distinct chars, the most a trie can code. The set is left
+    /// unchanged then.
+    fn extend<I: IntoIterator<Item = S>>(&mut self, keys: I) {
+        let keys: Vec<Vec<AlphaChar>> = keys
+            .into_iter()
+            .map(|key| {
+                let key: Vec<AlphaChar> = key.as_ref().chars().map(|c| c as AlphaChar).collect();
+                assert!(
+                    !key.contains(&0),
+                    "TrieSet::extend: key contains a nul char"
+                );
+                key
+            })
+            .collect();
+        self.insert_widening(&keys);
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for TrieSet {
+    /// Collect the keys into a set whose alpha map covers just their
+    /// characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics as [`extend`](Extend::extend) does.
+    fn from_iter<I: IntoIterator<Item = S>>(keys: I) -> Self {
+        let mut set = TrieSet::new(&AlphaMap::default()).unwrap();
+        set.extend(keys);
+        set
+    }
+}
+
+impl Iterator for Keys<'_> {
+    type Item = Vec<AlphaChar>;
+
+    fn next(&mut self) -> Option<Vec<AlphaChar>> {
+        self.iter.next().map(|(key, _)| key)
+    }
+}
+
+impl DoubleEndedIterator for Keys<'_> {
+    fn next_back(&mut self) -> Option<Vec<AlphaChar>> {
+        self.iter.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a> IntoIterator for &'a TrieSet {
+    type Item = Vec<AlphaChar>;
+    type IntoIter = Keys<'a>;
+
+    fn into_iter(self) -> Keys<'a> {
+        self.iter()
+    }
+}
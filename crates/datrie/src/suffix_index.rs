@@ -0,0 +1,232 @@
+use crate::{AlphaChar, AlphaStr, DatrieError, DatrieResult, ErrorKind, Trie, TrieChar, TrieData};
+
+/// A trie with a companion index for finding keys by their ending.
+///
+/// The index is a second trie holding every key reversed, so keys ending
+/// with a suffix are the reversed keys starting with the reversed suffix.
+/// It costs about as much memory as the trie itself.
+///
+/// In infix mode, see [`with_infix`](Self::with_infix), the index instead
+/// holds every rotation `key[i..] SEP key[..i]` of every key, where `SEP`
+/// is a char outside the alpha map. Keys containing an infix are then the
+/// rotations starting with it. This takes memory quadratic in key length:
+/// `n + 1` index keys of length `n + 1` for a key of length `n`.
+///
+/// Keys must be stored and deleted through the `SuffixIndex` to keep the
+/// index current.
+#[derive(Debug)]
+pub struct SuffixIndex {
+    trie: Trie,
+    index: Trie,
+    /// The rotation separator, in infix mode.
+    separator: Option<AlphaChar>,
+}
+
+impl SuffixIndex {
+    /// Index the keys of `trie` by suffix.
+    pub fn new(trie: Trie) -> DatrieResult<SuffixIndex> {
+        let index = Trie::new(&trie.alpha_map)?;
+        SuffixIndex::build(trie, index, None)
+    }
+
+    /// Index the keys of `trie` by infix, which also answers suffix
+    /// queries.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if the alpha map has no
+    /// trie-char code left for the separator.
+    pub fn with_infix(trie: Trie) -> DatrieResult<SuffixIndex> {
+        let full = || {
+            DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "SuffixIndex::with_infix: alpha map has no room for a separator".into(),
+            )
+        };
+        if trie.alpha_map.num_symbols() >= TrieChar::MAX as usize {
+            return Err(full());
+        }
+        // with fewer symbols than trie-char codes, one of the chars up to
+        // the number of codes is free
+        let separator = (1..=TrieChar::MAX as AlphaChar + 1)
+            .find(|&ac| trie.alpha_map.char_to_trie(ac).is_none())
+            .ok_or_else(full)?;
+        let mut alpha_map = trie.alpha_map.clone();
+        alpha_map.add_range(separator, separator)?;
+        let index = Trie::new(&alpha_map)?;
+        SuffixIndex::build(trie, index, Some(separator))
+    }
+
+    fn build(trie: Trie, index: Trie, separator: Option<AlphaChar>) -> DatrieResult<SuffixIndex> {
+        let mut suffix_index = SuffixIndex {
+            trie,
+            index,
+            separator,
+        };
+        let entries: Vec<_> = suffix_index.trie.iter().collect();
+        for (key, data) in entries {
+            suffix_index.store_index(&key, data)?;
+        }
+        Ok(suffix_index)
+    }
+
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+
+    pub fn into_trie(self) -> Trie {
+        self.trie
+    }
+
+    pub fn is_infix(&self) -> bool {
+        self.separator.is_some()
+    }
+}
+
+impl SuffixIndex {
+    pub fn retrieve(&self, key: &AlphaStr) -> Option<TrieData> {
+        self.trie.retrieve(key)
+    }
+
+    /// Store `data` for `key` in the trie and the index.
+    ///
+    /// If the index cannot hold the key, the store is undone in both.
+    pub fn store(&mut self, key: &AlphaStr, data: TrieData) -> bool {
+        let old_data = self.trie.retrieve(key);
+        if !self.trie.store(key, data) {
+            return false;
+        }
+        if self.store_index(key.to_slice(), data).is_ok() {
+            return true;
+        }
+        match old_data {
+            // the index keys of a stored key exist, so restoring their data
+            // allocates nothing
+            Some(old_data) => {
+                self.trie.store(key, old_data);
+                let _ = self.store_index(key.to_slice(), old_data);
+            }
+            None => {
+                self.delete(key);
+            }
+        }
+        false
+    }
+
+    /// Delete `key` from the trie and the index.
+    pub fn delete(&mut self, key: &AlphaStr) -> bool {
+        if !self.trie.delete(key) {
+            return false;
+        }
+        for index_key in self.index_keys(key.to_slice()) {
+            self.index
+                .delete(AlphaStr::from_slice_with_nul(&index_key).unwrap());
+        }
+        true
+    }
+
+    /// The entries whose keys end with `suffix`, in key order.
+    ///
+    /// A suffix with characters outside the alpha map gives no entries.
+    pub fn ends_with(&self, suffix: &AlphaStr) -> Vec<(Vec<AlphaChar>, TrieData)> {
+        if !self.in_alpha_map(suffix) {
+            return Vec::new();
+        }
+        let mut prefix: Vec<AlphaChar> = match self.separator {
+            None => suffix.to_slice().iter().rev().copied().collect(),
+            Some(separator) => [suffix.to_slice(), &[separator]].concat(),
+        };
+        prefix.push(0);
+        self.search(&prefix)
+    }
+
+    /// The entries whose keys contain `infix`, in key order.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if the index is not in
+    /// infix mode, or if `infix` has characters outside the alpha map.
+    pub fn contains_infix(
+        &self,
+        infix: &AlphaStr,
+    ) -> DatrieResult<Vec<(Vec<AlphaChar>, TrieData)>> {
+        if self.separator.is_none() {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "SuffixIndex::contains_infix: index is not in infix mode".into(),
+            ));
+        }
+        if !self.in_alpha_map(infix) {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "SuffixIndex::contains_infix: infix has characters outside the alpha map".into(),
+            ));
+        }
+        Ok(self.search(infix.to_slice_with_nul()))
+    }
+
+    /// Whether all chars of `query` are in the alpha map of the trie, which
+    /// leaves out the separator.
+    fn in_alpha_map(&self, query: &AlphaStr) -> bool {
+        query
+            .to_slice()
+            .iter()
+            .all(|&ac| self.trie.alpha_map.char_to_trie(ac).is_some())
+    }
+
+    /// The entries whose index keys start with the nul-terminated `prefix`.
+    fn search(&self, prefix: &[AlphaChar]) -> Vec<(Vec<AlphaChar>, TrieData)> {
+        let mut entries: Vec<_> = self
+            .index
+            .iter_prefix(AlphaStr::from_slice_with_nul(prefix).unwrap())
+            .map(|(index_key, data)| (self.original_key(&index_key), data))
+            .collect();
+        // an infix may occur at several places in a key
+        entries.sort_by_cached_key(|(key, _)| self.trie_key(key));
+        entries.dedup_by(|a, b| a.0 == b.0);
+        entries
+    }
+
+    fn store_index(&mut self, key: &[AlphaChar], data: TrieData) -> DatrieResult<()> {
+        for index_key in self.index_keys(key) {
+            if !self
+                .index
+                .store(AlphaStr::from_slice_with_nul(&index_key).unwrap(), data)
+            {
+                return Err(DatrieError::new(
+                    ErrorKind::Bug,
+                    "SuffixIndex: failed to store index key".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The nul-terminated index keys of `key`.
+    fn index_keys(&self, key: &[AlphaChar]) -> Vec<Vec<AlphaChar>> {
+        match self.separator {
+            None => {
+                let mut reversed: Vec<AlphaChar> = key.iter().rev().copied().collect();
+                reversed.push(0);
+                vec![reversed]
+            }
+            Some(separator) => (0..=key.len())
+                .map(|i| [&key[i..], &[separator], &key[..i], &[0]].concat())
+                .collect(),
+        }
+    }
+
+    /// The key that `index_key` was made from.
+    fn original_key(&self, index_key: &[AlphaChar]) -> Vec<AlphaChar> {
+        match self.separator {
+            None => index_key.iter().rev().copied().collect(),
+            Some(separator) => {
+                let sep = index_key.iter().position(|&ac| ac == separator).unwrap();
+                [&index_key[sep + 1..], &index_key[..sep]].concat()
+            }
+        }
+    }
+
+    /// `key` in trie chars, which sort in trie order.
+    fn trie_key(&self, key: &[AlphaChar]) -> Vec<i32> {
+        key.iter()
+            .map(|&ac| self.trie.alpha_map.char_to_trie(ac).unwrap_or(-1))
+            .collect()
+    }
+}
@@ -1,3 +1,4 @@
+use std::cell::{OnceCell, RefCell};
 use std::ffi::CStr;
 use std::path::Path;
 use std::{fs, io};
@@ -11,9 +12,29 @@ use crate::{alpha_map::*, darray::*, AlphaStr};
 use crate::{trie_string::*, DatrieError, DatrieResult, ErrorKind};
 use ::libc;
 
-use self::tail::Tail;
+pub use self::automaton::{Automaton, Search};
+pub use self::entry::{Entry, OccupiedEntry, VacantEntry};
+pub use self::entry_id::EntryId;
+pub use self::fuzzy::{EditDistance, FuzzyMatch};
+pub use self::iter::{Iter, ResumeToken};
+pub use self::merge::{Diff, DiffEntry};
+pub use self::pattern::Pattern;
+pub use self::range::Range;
+pub(crate) use self::tail::Tail;
 
+mod automaton;
+mod cursor;
+mod entry;
+mod entry_id;
+mod fuzzy;
+mod iter;
+mod merge;
+mod order;
+mod pattern;
+mod range;
+mod saved_state;
 mod tail;
+mod top_k;
 
 extern "C" {
     fn malloc(_: libc::c_ulong) -> *mut libc::c_void;
@@ -35,6 +56,31 @@ pub struct Trie {
     pub da: Box<DArray>,
     pub tail: Tail,
     pub is_dirty: bool,
+    /// Tail blocks whose data was lent out by `&mut` since the largest data
+    /// below each node was last brought up to date, see `lend_data`.
+    lent_data: RefCell<Vec<TrieIndex>>,
+    /// Hash of the serialized trie, built on demand by `fingerprint`.
+    fingerprint: OnceCell<u64>,
+}
+/// Where the walk of a trie-char key ends, see [`Trie::locate`].
+#[derive(Clone, Copy)]
+pub(crate) enum KeyPos {
+    /// The key is stored in tail block `tail`, below separate node `sep`.
+    Found { sep: TrieIndex, tail: TrieIndex },
+    /// Branch node `node` has no child for `key[depth]`.
+    InBranch { node: TrieIndex, depth: usize },
+    /// The key leaves the DArray at separate node `sep` with `key[depth..]`,
+    /// but does not match the tail suffix.
+    InTail { sep: TrieIndex, depth: usize },
+}
+/// Where the walk of a key prefix ends, see [`Trie::locate_prefix`].
+#[derive(Clone, Copy)]
+pub(crate) enum PrefixPos {
+    /// All keys below node `node` start with the prefix.
+    Node(TrieIndex),
+    /// Only the key of separate node `sep` starts with the prefix, which
+    /// leaves the DArray after `depth` chars.
+    InTail { sep: TrieIndex, depth: usize },
 }
 pub type TrieEnumFunc =
     Option<unsafe extern "C" fn(*const AlphaChar, TrieData, *mut libc::c_void) -> Bool>;
@@ -64,6 +110,8 @@ impl Trie {
             da,
             tail,
             is_dirty: true,
+            lent_data: RefCell::default(),
+            fingerprint: OnceCell::new(),
         })
     }
 
@@ -92,12 +140,19 @@ impl Trie {
         let alpha_map = AlphaMap::fread_bin_safe(reader)?;
         let da = Box::new(DArray::fread_safe(reader)?);
         let tail = Tail::fread_safe(reader)?;
-        Ok(Trie {
+        Ok(Trie::from_parts(alpha_map, da, tail))
+    }
+
+    /// A trie just read from its parts, so not dirty.
+    pub(crate) fn from_parts(alpha_map: AlphaMap, da: Box<DArray>, tail: Tail) -> Trie {
+        Trie {
             alpha_map,
             da,
             tail,
             is_dirty: false,
-        })
+            lent_data: RefCell::default(),
+            fingerprint: OnceCell::new(),
+        }
     }
 }
 
@@ -147,42 +202,32 @@ impl Trie {
     pub fn is_dirty(&self) -> bool {
         self.is_dirty
     }
-    pub fn retrieve(&self, key: &AlphaStr) -> Option<TrieData> {
-        let mut s = self.da.get_root();
-        let key_slice = key.to_slice_with_nul();
-        let mut p = key_slice;
-
-        while self.da.get_base(s) >= 0 as libc::c_int {
-            let Some(tc) = self.alpha_map.char_to_trie(p[0]) else {
-                return None;
-            };
 
-            if unsafe { self.da.walk(&mut s, tc as TrieChar) } as u64 == 0 {
-                return None;
-            }
-            if p[0] == 0 {
-                break;
-            }
-            p = &p[1..];
-        }
-        s = -self.da.get_base(s);
-        let mut suffix_idx = 0;
-        loop {
-            let Some(tc_0) = self.alpha_map.char_to_trie(p[0]) else {
-                return None;
-            };
-
-            if !self.tail.walk_char(s, &mut suffix_idx, tc_0 as TrieChar) {
-                return None;
-            }
+    /// Note a change to the keys or data, dropping derived caches.
+    pub(crate) fn mark_dirty(&mut self) {
+        self.is_dirty = true;
+        self.fingerprint.take();
+    }
 
-            if p[0] == 0 {
-                break;
+    /// Hand out the data in tail block `tail` for modification.
+    ///
+    /// The new data is not known yet, so the block is noted for updating
+    /// the largest data below each node on the next top-k query.
+    pub(crate) fn lend_data(&mut self, tail: TrieIndex) -> Option<&mut TrieData> {
+        self.mark_dirty();
+        if self.da.has_max_data() {
+            let lent = self.lent_data.get_mut();
+            lent.push(tail);
+            if lent.len() > self.tail.num_tails() {
+                lent.sort_unstable();
+                lent.dedup();
             }
-            p = &p[1..];
         }
-
-        self.tail.get_data(s)
+        self.tail.get_data_mut(tail)
+    }
+    pub fn retrieve(&self, key: &AlphaStr) -> Option<TrieData> {
+        let key = self.alpha_map.char_to_trie_str(key)?;
+        self.retrieve_trie_key(key.as_bytes_with_nul())
     }
 
     pub fn store(&mut self, key: &AlphaStr, data: TrieData) -> bool {
@@ -194,62 +239,104 @@ impl Trie {
     }
 
     fn store_conditionally(&mut self, key: &AlphaStr, data: TrieData, is_overwrite: bool) -> bool {
+        let Some(key) = self.alpha_map.char_to_trie_str(key) else {
+            return false;
+        };
+        self.store_trie_key(key.as_bytes_with_nul(), data, is_overwrite)
+    }
+
+    /// Walk the nul-terminated trie-char `key` as far as it goes.
+    pub(crate) fn locate(&self, key: &[TrieChar]) -> KeyPos {
         let mut s = self.da.get_root();
-        let mut p = key.to_slice_with_nul();
-        while self.da.get_base(s) >= 0 as libc::c_int {
-            let Some(tc) = self.alpha_map.char_to_trie(p[0]) else {
-                return false;
-            };
-            if unsafe { self.da.walk(&mut s, tc as TrieChar) } as u64 == 0 {
-                let Some(key_str) = self
-                    .alpha_map
-                    .char_to_trie_str(AlphaStr::from_slice_with_nul(p).unwrap())
-                else {
-                    return false;
-                };
-                dbg!(&key_str);
-                let res = self.branch_in_branch(s, key_str.as_trie_str(), data);
-                return res;
+        let mut depth = 0;
+        while self.da.get_base(s) >= 0 {
+            if unsafe { self.da.walk(&mut s, key[depth]) } == DA_FALSE {
+                return KeyPos::InBranch { node: s, depth };
             }
-            if p[0] == 0 {
+            if key[depth] == 0 {
                 break;
             }
-            p = &p[1..];
+            depth += 1;
         }
-        let sep = p;
-        dbg!(sep[0]);
-        let t = -(*self.da).get_base(s);
+        let t = -self.da.get_base(s);
         let mut suffix_idx = 0;
-        loop {
-            let Some(tc_0) = self.alpha_map.char_to_trie(p[0]) else {
-                return false;
-            };
-            dbg!(&tc_0);
-            if !self.tail.walk_char(t, &mut suffix_idx, tc_0 as TrieChar) {
-                dbg!(&suffix_idx);
-
-                if let Some(tail_str) = self
-                    .alpha_map
-                    .char_to_trie_str(AlphaStr::from_slice_with_nul(sep).unwrap())
-                {
-                    dbg!(&tail_str);
-                    let res_0 = self.branch_in_tail(s, tail_str, data);
-                    return res_0;
-                } else {
-                    return false;
-                }
+        for &tc in &key[depth..] {
+            if !self.tail.walk_char(t, &mut suffix_idx, tc) {
+                return KeyPos::InTail { sep: s, depth };
             }
-            if p[0] == 0 {
+            if tc == 0 {
                 break;
             }
-            p = &p[1..];
         }
-        if is_overwrite {
-            return false;
+        KeyPos::Found { sep: s, tail: t }
+    }
+
+    /// Walk the trie-char `prefix` (without terminator).
+    ///
+    /// Returns `None` if no key starts with `prefix`.
+    pub(crate) fn locate_prefix(&self, prefix: &[TrieChar]) -> Option<PrefixPos> {
+        let mut s = self.da.get_root();
+        for (depth, &tc) in prefix.iter().enumerate() {
+            if self.da.get_base(s) < 0 {
+                let suffix = self.tail.get_suffix(-self.da.get_base(s))?;
+                return suffix
+                    .to_bytes()
+                    .starts_with(&prefix[depth..])
+                    .then_some(PrefixPos::InTail { sep: s, depth });
+            }
+            if unsafe { self.da.walk(&mut s, tc) } == DA_FALSE {
+                return None;
+            }
+        }
+        Some(PrefixPos::Node(s))
+    }
+
+    pub(crate) fn retrieve_trie_key(&self, key: &[TrieChar]) -> Option<TrieData> {
+        match self.locate(key) {
+            KeyPos::Found { tail, .. } => self.tail.get_data(tail),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn store_trie_key(
+        &mut self,
+        key: &[TrieChar],
+        data: TrieData,
+        is_overwrite: bool,
+    ) -> bool {
+        let pos = self.locate(key);
+        self.store_at(key, pos, data, is_overwrite).is_some()
+    }
+
+    /// Store `data` for `key`, which was walked up to `pos`.
+    ///
+    /// Returns the tail block holding `data`, or `None` if nothing was stored.
+    pub(crate) fn store_at(
+        &mut self,
+        key: &[TrieChar],
+        pos: KeyPos,
+        data: TrieData,
+        is_overwrite: bool,
+    ) -> Option<TrieIndex> {
+        match pos {
+            KeyPos::Found { sep, tail } => {
+                if !is_overwrite {
+                    return None;
+                }
+                self.tail.set_data(tail, data);
+                self.da.set_max_data(sep, data);
+                self.mark_dirty();
+                Some(tail)
+            }
+            KeyPos::InBranch { node, depth } => {
+                let suffix = TrieCharStr::from_bytes_with_nul(&key[depth..]).unwrap();
+                self.branch_in_branch(node, suffix, data)
+            }
+            KeyPos::InTail { sep, depth } => {
+                let suffix = TrieCharString::from_vec_with_nul(key[depth..].to_vec()).unwrap();
+                self.branch_in_tail(sep, suffix, data)
+            }
         }
-        self.tail.set_data(t, data);
-        self.is_dirty = true;
-        true
     }
 
     fn branch_in_branch(
@@ -258,12 +345,12 @@ impl Trie {
         suffix: &TrieCharStr,
 
         data: TrieData,
-    ) -> bool {
+    ) -> Option<TrieIndex> {
         let mut suffix_bytes = suffix.to_bytes_with_nul();
         dbg!(suffix_bytes[0]);
         let new_da = unsafe { self.da.insert_branch(sep_node, suffix_bytes[0]) };
         if 0 as libc::c_int == new_da {
-            return false;
+            return None;
         }
         if suffix_bytes[0] != 0 {
             suffix_bytes = &suffix_bytes[1..];
@@ -274,9 +361,11 @@ impl Trie {
             .tail
             .add_suffix(TrieCharString::from_vec_with_nul(suffix_bytes.to_vec()).unwrap());
         self.tail.set_data(new_tail, data);
-        self.da.set_base(new_da, -new_tail);
-        self.is_dirty = true;
-        true
+        self.da.set_tail_node(new_da, new_tail);
+        self.da.add_count(new_da, None, 1);
+        self.da.set_max_data(new_da, data);
+        self.mark_dirty();
+        Some(new_tail)
     }
 
     fn branch_in_tail(
@@ -284,13 +373,11 @@ impl Trie {
         sep_node: TrieIndex,
         suffix: TrieCharString,
         data: TrieData,
-    ) -> bool {
+    ) -> Option<TrieIndex> {
         let current_block: u64;
 
         let old_tail = -(*self.da).get_base(sep_node);
-        let Some(old_suffix) = self.tail.take_suffix(old_tail) else {
-            return false;
-        };
+        let old_suffix = self.tail.take_suffix(old_tail)?;
         let mut p = old_suffix.to_bytes_with_nul();
         let mut suffix_bytes = suffix.to_bytes_with_nul();
         let mut s = sep_node;
@@ -326,7 +413,11 @@ impl Trie {
                     old_tail,
                     TrieCharString::from_vec_with_nul(p.to_vec()).unwrap(),
                 );
-                self.da.set_base(old_da, -old_tail);
+                self.da.set_tail_node(old_da, old_tail);
+                // the nodes added for the old key are new to the count
+                self.da.add_count(old_da, Some(sep_node), 1);
+                let old_data = self.tail.get_data(old_tail).unwrap_or(-1);
+                self.da.set_max_data(old_da, old_data);
                 return self.branch_in_branch(
                     s,
                     &TrieCharStr::from_bytes_with_nul(suffix_bytes).unwrap(),
@@ -335,54 +426,59 @@ impl Trie {
             }
         }
         self.da.prune_upto(sep_node, s);
-        self.da.set_base(sep_node, -old_tail);
-        false
+        self.da.set_tail_node(sep_node, old_tail);
+        None
     }
 }
 
 impl Trie {
     pub fn delete(&mut self, key: &AlphaStr) -> bool {
-        // let mut s: TrieIndex = 0;
-        // let mut t: TrieIndex = 0;
-        // let mut suffix_idx: libc::c_short = 0;
-        // let mut p: *const AlphaChar = 0 as *const AlphaChar;
-        let mut s = (*self.da).get_root();
-        let key_slice = key.to_slice_with_nul();
-        let mut p = key_slice;
-        while (*self.da).get_base(s) >= 0 as libc::c_int {
-            let Some(tc) = self.alpha_map.char_to_trie(p[0]) else {
-                return false;
-            };
-            if unsafe { self.da.walk(&mut s, tc as TrieChar) } as u64 == 0 {
-                return false;
-            }
-            if p[0] == 0 {
-                break;
-            }
-            p = &p[1..];
-        }
-        let t = -(*self.da).get_base(s);
-        // suffix_idx = 0 as libc::c_int as libc::c_short;
-        let mut suffix_idx = 0;
-        loop {
-            let Some(tc_0) = self.alpha_map.char_to_trie(p[0]) else {
-                return false;
-            };
-            if !self.tail.walk_char(t, &mut suffix_idx, tc_0 as TrieChar) {
-                return false;
-            }
-            if p[0] == 0 {
-                break;
-            }
-            p = &p[1..];
-        }
+        self.remove(key).is_some()
+    }
+
+    pub(crate) fn remove_trie_key(&mut self, key: &[TrieChar]) -> Option<TrieData> {
+        let KeyPos::Found { sep, tail } = self.locate(key) else {
+            return None;
+        };
+        let data = self.tail.get_data(tail);
+        self.remove_at(sep, tail);
+        data
+    }
+
+    /// Delete the key stored in tail block `tail`, below separate node `sep`.
+    pub(crate) fn remove_at(&mut self, sep: TrieIndex, tail: TrieIndex) {
         unsafe {
-            self.tail.delete(t);
+            self.tail.delete(tail);
         }
-        self.da.set_base(s, 0 as libc::c_int);
-        self.da.prune(s);
-        self.is_dirty = true;
-        true
+        self.da.add_count(sep, None, -1);
+        self.da.set_base(sep, 0 as libc::c_int);
+        self.da.set_max_data(sep, TrieData::MIN);
+        self.da.prune(sep);
+        self.mark_dirty();
+    }
+
+    /// Store `data` for the nul-terminated `key`, returning the data it
+    /// replaced.
+    ///
+    /// Fails with [`ErrorKind::Memory`] if the double-array cannot grow to
+    /// hold the key.
+    pub(crate) fn insert_trie_key(
+        &mut self,
+        key: &[TrieChar],
+        data: TrieData,
+    ) -> DatrieResult<Option<TrieData>> {
+        let pos = self.locate(key);
+        let old_data = match pos {
+            KeyPos::Found { tail, .. } => self.tail.get_data(tail),
+            _ => None,
+        };
+        if self.store_at(key, pos, data, true).is_none() {
+            return Err(DatrieError::new(
+                ErrorKind::Memory,
+                "double-array is full".into(),
+            ));
+        }
+        Ok(old_data)
     }
 
     pub unsafe fn enumerate(
@@ -426,7 +522,229 @@ impl Trie {
     }
 }
 
+impl Trie {
+    /// Get the data stored for `key`.
+    pub fn get(&self, key: &AlphaStr) -> Option<TrieData> {
+        self.retrieve(key)
+    }
+
+    pub fn contains_key(&self, key: &AlphaStr) -> bool {
+        self.retrieve(key).is_some()
+    }
+
+    /// Store `data` for `key`, returning the data it replaced.
+    ///
+    /// Returns `None` if `key` was not in the trie.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` has characters outside the alpha map, or if the
+    /// double-array cannot grow to hold it. Use
+    /// [`try_insert`](Self::try_insert) to get these as errors.
+    pub fn insert(&mut self, key: &AlphaStr, data: TrieData) -> Option<TrieData> {
+        self.try_insert(key, data)
+            .unwrap_or_else(|err| panic!("Trie::insert: {}", err))
+    }
+
+    /// Store `data` for `key`, returning the data it replaced.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if `key` has characters
+    /// outside the alpha map, and with [`ErrorKind::Memory`] if the
+    /// double-array cannot grow to hold it.
+    pub fn try_insert(&mut self, key: &AlphaStr, data: TrieData) -> DatrieResult<Option<TrieData>> {
+        let key = self.alpha_map.char_to_trie_str(key).ok_or_else(|| {
+            DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "key has characters outside the alpha map".into(),
+            )
+        })?;
+        self.insert_trie_key(key.as_bytes_with_nul(), data)
+    }
+
+    /// Delete `key`, returning the data that was stored for it.
+    pub fn remove(&mut self, key: &AlphaStr) -> Option<TrieData> {
+        let key = self.alpha_map.char_to_trie_str(key)?;
+        self.remove_trie_key(key.as_bytes_with_nul())
+    }
+
+    /// Delete all keys that start with `prefix`, returning how many there
+    /// were.
+    ///
+    /// The subtree at the end of `prefix` is freed in bulk, rather than
+    /// deleting its keys one by one.
+    pub fn remove_prefix(&mut self, prefix: &AlphaStr) -> usize {
+        let Some(prefix) = self.alpha_map.char_to_trie_str(prefix) else {
+            return 0;
+        };
+        let removed = match self.locate_prefix(prefix.to_bytes()) {
+            None => return 0,
+            Some(PrefixPos::InTail { sep, .. }) => {
+                let tail = -self.da.get_base(sep);
+                self.remove_at(sep, tail);
+                return 1;
+            }
+            Some(PrefixPos::Node(s)) => {
+                let mut tails = self.da.remove_subtree(s);
+                tails.sort_unstable();
+                self.tail.delete_many(&tails);
+                tails.len()
+            }
+        };
+        if removed > 0 {
+            self.mark_dirty();
+        }
+        removed
+    }
+
+    /// Number of keys in the trie.
+    ///
+    /// This takes constant time with key counting on, see
+    /// [`set_key_counting`](Self::set_key_counting), and walks the whole
+    /// trie otherwise.
+    pub fn len(&self) -> usize {
+        self.count_below(self.da.get_root())
+    }
+
+    /// Number of keys that start with `prefix`.
+    ///
+    /// With key counting on, this only walks `prefix`.
+    pub fn count_prefix(&self, prefix: &AlphaStr) -> usize {
+        let Some(prefix) = self.alpha_map.char_to_trie_str(prefix) else {
+            return 0;
+        };
+        match self.locate_prefix(prefix.to_bytes()) {
+            None => 0,
+            Some(PrefixPos::InTail { .. }) => 1,
+            Some(PrefixPos::Node(s)) => self.count_below(s),
+        }
+    }
+
+    /// Keep a count of the keys below each node, or drop the counts.
+    ///
+    /// The counts make [`len`](Self::len) and
+    /// [`count_prefix`](Self::count_prefix) fast, at the cost of one `u32`
+    /// per double-array cell. They are off by default and are not saved
+    /// with the trie; turning them on counts all keys once.
+    pub fn set_key_counting(&mut self, enabled: bool) {
+        self.da.set_counting(enabled);
+    }
+
+    pub fn is_key_counting(&self) -> bool {
+        self.da.count(self.da.get_root()).is_some()
+    }
+
+    fn count_below(&self, s: TrieIndex) -> usize {
+        if let Some(count) = self.da.count(s) {
+            return count;
+        }
+        let mut count = 0;
+        self.walk_entries(s, &mut Vec::new(), &mut |_, _| {
+            count += 1;
+            true
+        });
+        count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.da.output_symbols(self.da.get_root()).num() == 0
+    }
+}
+
+impl Trie {
+    /// Rebuild this trie under another alphabet map.
+    ///
+    /// Every key is decoded through the current `alpha_map` and stored again
+    /// in a new trie that codes its chars through `alpha_map`. Fails with
+    /// [`ErrorKind::InvalidArgument`] if some key contains a character that
+    /// the new map does not cover.
+    pub fn remap_alphabet(&self, alpha_map: &AlphaMap) -> DatrieResult<Trie> {
+        let mut trie = Trie::new(alpha_map)?;
+        let mut result = Ok(());
+        let mut alpha_key = Vec::new();
+        self.walk_entries(self.da.get_root(), &mut Vec::new(), &mut |key, data| {
+            alpha_key.clear();
+            for &tc in key {
+                let ac = self.alpha_map.trie_to_char(tc);
+                if alpha_map.char_to_trie(ac).is_none() {
+                    result = Err(DatrieError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "remap_alphabet: character {:#x} is not in the new alpha map",
+                            ac
+                        ),
+                    ));
+                    return false;
+                }
+                alpha_key.push(ac);
+            }
+            alpha_key.push(0);
+            if !trie.store(AlphaStr::from_slice_with_nul(&alpha_key).unwrap(), data) {
+                result = Err(DatrieError::new(
+                    ErrorKind::Bug,
+                    "remap_alphabet: failed to store key".into(),
+                ));
+                return false;
+            }
+            true
+        });
+        result.map(|_| trie)
+    }
+
+    /// Call `f` with the trie-char key (without terminator) and the data of
+    /// every entry below node `s`, in trie-char order.
+    ///
+    /// Stops and returns `false` as soon as `f` returns `false`.
+    pub(crate) fn walk_entries(
+        &self,
+        s: TrieIndex,
+        key: &mut Vec<TrieChar>,
+        f: &mut dyn FnMut(&[TrieChar], TrieData) -> bool,
+    ) -> bool {
+        let base = self.da.get_base(s);
+        if base < 0 {
+            let t = -base;
+            let key_len = key.len();
+            if let Some(suffix) = self.tail.get_suffix(t) {
+                key.extend_from_slice(suffix.to_bytes());
+            }
+            let cont = f(key, self.tail.get_data(t).unwrap_or(-1));
+            key.truncate(key_len);
+            return cont;
+        }
+        if base == 0 {
+            return true;
+        }
+        let syms = self.da.output_symbols(s);
+        for i in 0..syms.num() {
+            let c = syms.get(i);
+            if c != 0 {
+                key.push(c);
+            }
+            let cont = self.walk_entries(base + c as TrieIndex, key, f);
+            if c != 0 {
+                key.pop();
+            }
+            if !cont {
+                return false;
+            }
+        }
+        true
+    }
+}
+
 impl TrieState {
+    /// A state at the root of `trie`, held by value.
+    ///
+    /// The state points into `trie`, so it must not outlive it.
+    pub(crate) fn at_root(trie: &Trie) -> TrieState {
+        TrieState {
+            trie,
+            index: trie.da.get_root(),
+            suffix_idx: 0,
+            is_suffix: DA_FALSE as libc::c_short,
+        }
+    }
+
     unsafe fn new(
         trie: *const Trie,
         index: TrieIndex,
@@ -502,7 +820,6 @@ impl TrieState {
             let Some(suffix) = (*(*s).trie).tail.get_suffix((*s).index) else {
                 return DA_FALSE;
             };
-            dbg!(tc as TrieChar);
             assert!((*s).suffix_idx <= suffix.to_bytes().len());
             (suffix.to_bytes_with_nul()[(*s).suffix_idx] == tc as TrieChar) as Bool
             // (*((*(*s).trie).tail.get_suffix((*s).index))[(*s).suffix_idx] == tc as TrieChar) as Bool
@@ -0,0 +1,155 @@
+use crate::darray::Symbols;
+use crate::trie::{AlphaChar, Trie, TrieData, TrieIndex};
+
+/// A deterministic automaton over alpha chars, run against a trie by
+/// [`Trie::search`].
+pub trait Automaton {
+    type State: Clone;
+
+    /// The state before any char is read.
+    fn start(&self) -> Self::State;
+
+    /// The state after reading `ac` in `state`.
+    fn accept(&self, state: &Self::State, ac: AlphaChar) -> Self::State;
+
+    /// Whether the chars read up to `state` are accepted.
+    fn is_match(&self, state: &Self::State) -> bool;
+
+    /// Whether some continuation from `state` can still be accepted.
+    ///
+    /// Returning `false` lets the search skip the whole subtree, so dead
+    /// states should be reported here.
+    fn can_match(&self, _state: &Self::State) -> bool {
+        true
+    }
+}
+
+impl<A: Automaton + ?Sized> Automaton for &A {
+    type State = A::State;
+
+    fn start(&self) -> Self::State {
+        (**self).start()
+    }
+
+    fn accept(&self, state: &Self::State, ac: AlphaChar) -> Self::State {
+        (**self).accept(state, ac)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        (**self).is_match(state)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        (**self).can_match(state)
+    }
+}
+
+/// Iterator over the keys accepted by an automaton, see [`Trie::search`].
+pub struct Search<'a, A: Automaton> {
+    trie: &'a Trie,
+    aut: A,
+    /// Branch nodes being walked, from the root down.
+    stack: Vec<SearchFrame<A::State>>,
+    key: Vec<AlphaChar>,
+}
+
+struct SearchFrame<S> {
+    base: TrieIndex,
+    syms: Symbols,
+    next: usize,
+    state: S,
+    key_len: usize,
+}
+
+impl Trie {
+    /// Iterate over the keys accepted by `aut`, with their data, in key
+    /// order.
+    ///
+    /// The automaton is stepped along the double-array and the tail
+    /// suffixes, and no branch is entered once `aut` reports that it cannot
+    /// match.
+    pub fn search<A: Automaton>(&self, aut: A) -> Search<'_, A> {
+        let mut search = Search {
+            trie: self,
+            aut,
+            stack: Vec::new(),
+            key: Vec::new(),
+        };
+        let state = search.aut.start();
+        if search.aut.can_match(&state) {
+            // the root is a branch node, so entering it yields nothing
+            search.enter(self.da.get_root(), state);
+        }
+        search
+    }
+}
+
+impl<A: Automaton> Search<'_, A> {
+    /// Enter node `s` in `state`, with the key walked so far in `self.key`.
+    ///
+    /// Branch nodes are pushed on the stack. For a separate node, the tail
+    /// suffix is walked and the entry is returned if it is accepted.
+    fn enter(&mut self, s: TrieIndex, mut state: A::State) -> Option<(Vec<AlphaChar>, TrieData)> {
+        let trie = self.trie;
+        let base = trie.da.get_base(s);
+        if base < 0 {
+            let t = -base;
+            let suffix = trie.tail.get_suffix(t).map_or(&[][..], |s| s.to_bytes());
+            for &tc in suffix {
+                let ac = trie.alpha_map.trie_to_char(tc);
+                state = self.aut.accept(&state, ac);
+                if !self.aut.can_match(&state) {
+                    return None;
+                }
+                self.key.push(ac);
+            }
+            if self.aut.is_match(&state) {
+                return Some((self.key.clone(), trie.tail.get_data(t).unwrap_or(-1)));
+            }
+            return None;
+        }
+        if base == 0 {
+            return None;
+        }
+        self.stack.push(SearchFrame {
+            base,
+            syms: trie.da.output_symbols(s),
+            next: 0,
+            state,
+            key_len: self.key.len(),
+        });
+        None
+    }
+}
+
+impl<A: Automaton> Iterator for Search<'_, A> {
+    type Item = (Vec<AlphaChar>, TrieData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(frame) = self.stack.last_mut() {
+            if frame.next == frame.syms.num() {
+                self.stack.pop();
+                continue;
+            }
+            let c = frame.syms.get(frame.next);
+            frame.next += 1;
+            self.key.truncate(frame.key_len);
+            let child = frame.base + c as TrieIndex;
+            let state = if c == 0 {
+                frame.state.clone()
+            } else {
+                let ac = self.trie.alpha_map.trie_to_char(c);
+                let state = self.aut.accept(&frame.state, ac);
+                if !self.aut.can_match(&state) {
+                    continue;
+                }
+                self.key.push(ac);
+                state
+            };
+            if let Some(entry) = self.enter(child, state) {
+                return Some(entry);
+            }
+        }
+        None
+    }
+}
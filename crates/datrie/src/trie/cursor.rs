@@ -0,0 +1,223 @@
+use std::cmp::Ordering;
+
+use crate::trie::{AlphaChar, Trie, TrieChar, TrieData, TrieIndex};
+
+/// A position at one entry of a trie, which moves in key order.
+///
+/// The position is the path of branch nodes from the root with the symbol
+/// taken at each, ending at the separate node of the entry. Keys are
+/// compared as trie-char strings, where the terminator sorts first.
+#[derive(Clone)]
+pub(crate) struct Cursor<'a> {
+    trie: &'a Trie,
+    path: Vec<(TrieIndex, TrieChar)>,
+    valid: bool,
+}
+
+impl<'a> Cursor<'a> {
+    /// A cursor positioned nowhere.
+    pub(crate) fn new(trie: &'a Trie) -> Cursor<'a> {
+        Cursor {
+            trie,
+            path: Vec::new(),
+            valid: false,
+        }
+    }
+
+    pub(crate) fn trie(&self) -> &'a Trie {
+        self.trie
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// The separate node of the current entry.
+    fn leaf(&self) -> TrieIndex {
+        match self.path.last() {
+            Some(&(s, c)) => self.trie.da.get_base(s) + c as TrieIndex,
+            None => self.trie.da.get_root(),
+        }
+    }
+
+    /// Whether two cursors of the same trie are at the same entry.
+    pub(crate) fn same_entry(&self, other: &Cursor) -> bool {
+        self.leaf() == other.leaf()
+    }
+
+    /// Compare the keys of the entries of two cursors of the same trie.
+    ///
+    /// Paths of distinct entries part at some branch node, so comparing
+    /// the symbols taken along them orders the keys without building them.
+    pub(crate) fn cmp_entry(&self, other: &Cursor) -> Ordering {
+        self.path
+            .iter()
+            .map(|&(_, c)| c)
+            .cmp(other.path.iter().map(|&(_, c)| c))
+    }
+
+    /// The trie-char key of the current entry, without terminator.
+    pub(crate) fn key(&self) -> Vec<TrieChar> {
+        let mut key: Vec<TrieChar> = self
+            .path
+            .iter()
+            .map(|&(_, c)| c)
+            .filter(|&c| c != 0)
+            .collect();
+        let t = -self.trie.da.get_base(self.leaf());
+        if let Some(suffix) = self.trie.tail.get_suffix(t) {
+            key.extend_from_slice(suffix.to_bytes());
+        }
+        key
+    }
+
+    /// The alpha key and data of the current entry.
+    pub(crate) fn entry(&self) -> (Vec<AlphaChar>, TrieData) {
+        let alpha_key = self
+            .key()
+            .iter()
+            .map(|&tc| self.trie.alpha_map.trie_to_char(tc))
+            .collect();
+        (alpha_key, self.data())
+    }
+
+    /// The data of the current entry.
+    pub(crate) fn data(&self) -> TrieData {
+        let t = -self.trie.da.get_base(self.leaf());
+        self.trie.tail.get_data(t).unwrap_or(-1)
+    }
+
+    pub(crate) fn seek_first(&mut self) -> bool {
+        self.path.clear();
+        self.descend(self.trie.da.get_root(), Direction::Forward)
+    }
+
+    pub(crate) fn seek_last(&mut self) -> bool {
+        self.path.clear();
+        self.descend(self.trie.da.get_root(), Direction::Backward)
+    }
+
+    /// Move to the first entry with key `>= target`, or `> target` if
+    /// `inclusive` is false.
+    pub(crate) fn seek_ge(&mut self, target: &[TrieChar], inclusive: bool) -> bool {
+        self.seek(target, inclusive, Direction::Forward)
+    }
+
+    /// Move to the last entry with key `<= target`, or `< target` if
+    /// `inclusive` is false.
+    pub(crate) fn seek_le(&mut self, target: &[TrieChar], inclusive: bool) -> bool {
+        self.seek(target, inclusive, Direction::Backward)
+    }
+
+    /// Move to the entry after the current one.
+    pub(crate) fn next(&mut self) -> bool {
+        self.step(Direction::Forward)
+    }
+
+    /// Move to the entry before the current one.
+    pub(crate) fn prev(&mut self) -> bool {
+        self.step(Direction::Backward)
+    }
+
+    fn seek(&mut self, target: &[TrieChar], inclusive: bool, dir: Direction) -> bool {
+        let trie = self.trie;
+        let da = &trie.da;
+        self.path.clear();
+        let mut s = da.get_root();
+        let mut depth = 0;
+        loop {
+            let base = da.get_base(s);
+            if base < 0 {
+                let suffix = trie
+                    .tail
+                    .get_suffix(-base)
+                    .map_or(&[][..], |suffix| suffix.to_bytes());
+                self.valid = true;
+                return match (suffix.cmp(&target[depth..]), dir) {
+                    (Ordering::Equal, _) if inclusive => true,
+                    (Ordering::Greater, Direction::Forward) => true,
+                    (Ordering::Less, Direction::Backward) => true,
+                    _ => self.step(dir),
+                };
+            }
+            if base == 0 {
+                self.valid = false;
+                return false;
+            }
+            let tc = target.get(depth).copied().unwrap_or(0);
+            let syms = da.output_symbols(s);
+            let symbols = (0..syms.num()).map(|i| syms.get(i));
+            let found = match dir {
+                Direction::Forward => symbols.filter(|&c| c >= tc).min(),
+                Direction::Backward => symbols.filter(|&c| c <= tc).max(),
+            };
+            match found {
+                Some(c) if c == tc => {
+                    self.path.push((s, c));
+                    s = base + c as TrieIndex;
+                    if c != 0 {
+                        depth += 1;
+                    }
+                }
+                Some(c) => {
+                    self.path.push((s, c));
+                    return self.descend(base + c as TrieIndex, dir);
+                }
+                None => {
+                    self.valid = true;
+                    return self.step(dir);
+                }
+            }
+        }
+    }
+
+    /// Move to the nearest entry in `dir` outside the current path's last
+    /// branch.
+    fn step(&mut self, dir: Direction) -> bool {
+        if !self.valid {
+            return false;
+        }
+        while let Some((s, c)) = self.path.pop() {
+            let syms = self.trie.da.output_symbols(s);
+            let symbols = (0..syms.num()).map(|i| syms.get(i));
+            let sibling = match dir {
+                Direction::Forward => symbols.filter(|&sym| sym > c).min(),
+                Direction::Backward => symbols.filter(|&sym| sym < c).max(),
+            };
+            if let Some(sibling) = sibling {
+                self.path.push((s, sibling));
+                return self.descend(self.trie.da.get_base(s) + sibling as TrieIndex, dir);
+            }
+        }
+        self.valid = false;
+        false
+    }
+
+    /// Extend the path from node `s` to its first or last entry.
+    fn descend(&mut self, mut s: TrieIndex, dir: Direction) -> bool {
+        loop {
+            let base = self.trie.da.get_base(s);
+            if base < 0 {
+                self.valid = true;
+                return true;
+            }
+            let syms = self.trie.da.output_symbols(s);
+            if base == 0 || syms.num() == 0 {
+                self.valid = false;
+                return false;
+            }
+            let c = match dir {
+                Direction::Forward => syms.get(0),
+                Direction::Backward => syms.get(syms.num() - 1),
+            };
+            self.path.push((s, c));
+            s = base + c as TrieIndex;
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Forward,
+    Backward,
+}
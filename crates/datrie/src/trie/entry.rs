@@ -0,0 +1,149 @@
+use crate::trie::{KeyPos, Trie, TrieCharString, TrieData, TrieIndex};
+use crate::{AlphaStr, DatrieError, DatrieResult, ErrorKind};
+
+/// A view into a single key of a [`Trie`], see [`Trie::entry`].
+pub enum Entry<'a> {
+    Occupied(OccupiedEntry<'a>),
+    Vacant(VacantEntry<'a>),
+}
+
+/// An entry for a key that is stored in the trie.
+pub struct OccupiedEntry<'a> {
+    trie: &'a mut Trie,
+    sep: TrieIndex,
+    tail: TrieIndex,
+}
+
+/// An entry for a key that is not in the trie.
+///
+/// It remembers where the walk of the key stopped, so inserting does not
+/// walk the key again.
+pub struct VacantEntry<'a> {
+    trie: &'a mut Trie,
+    key: TrieCharString,
+    pos: KeyPos,
+}
+
+impl Trie {
+    /// Get the entry for `key` for in-place updates.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if `key` has characters
+    /// outside the alpha map.
+    pub fn entry(&mut self, key: &AlphaStr) -> DatrieResult<Entry<'_>> {
+        let Some(key) = self.alpha_map.char_to_trie_str(key) else {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "Trie::entry: key has characters outside the alpha map".into(),
+            ));
+        };
+        Ok(match self.locate(key.as_bytes_with_nul()) {
+            KeyPos::Found { sep, tail } => Entry::Occupied(OccupiedEntry {
+                trie: self,
+                sep,
+                tail,
+            }),
+            pos => Entry::Vacant(VacantEntry {
+                trie: self,
+                key,
+                pos,
+            }),
+        })
+    }
+
+    /// Get a mutable reference to the data stored for `key`.
+    pub fn get_mut(&mut self, key: &AlphaStr) -> Option<&mut TrieData> {
+        let key = self.alpha_map.char_to_trie_str(key)?;
+        let KeyPos::Found { tail, .. } = self.locate(key.as_bytes_with_nul()) else {
+            return None;
+        };
+        self.lend_data(tail)
+    }
+}
+
+impl<'a> Entry<'a> {
+    /// Insert `default` if the key is vacant, and return its data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the double-array cannot grow to hold the key.
+    pub fn or_insert(self, default: TrieData) -> &'a mut TrieData {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insert the result of `default` if the key is vacant, and return its
+    /// data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the double-array cannot grow to hold the key.
+    pub fn or_insert_with<F: FnOnce() -> TrieData>(self, default: F) -> &'a mut TrieData {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Call `f` on the data if the key is occupied.
+    pub fn and_modify<F: FnOnce(&mut TrieData)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    pub fn get(&self) -> TrieData {
+        self.trie.tail.get_data(self.tail).unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut TrieData {
+        self.trie.lend_data(self.tail).unwrap()
+    }
+
+    pub fn into_mut(self) -> &'a mut TrieData {
+        self.trie.lend_data(self.tail).unwrap()
+    }
+
+    /// Replace the data, returning the old data.
+    pub fn insert(&mut self, data: TrieData) -> TrieData {
+        std::mem::replace(self.get_mut(), data)
+    }
+
+    /// Delete the key from the trie, returning its data.
+    pub fn remove(self) -> TrieData {
+        let data = self.get();
+        self.trie.remove_at(self.sep, self.tail);
+        data
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Store `data` for the key, and return a reference to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the double-array cannot grow to hold the key; use
+    /// [`try_insert`](Self::try_insert) to handle that case.
+    pub fn insert(self, data: TrieData) -> &'a mut TrieData {
+        self.try_insert(data)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Store `data` for the key, and return a reference to it.
+    ///
+    /// Fails with [`ErrorKind::Memory`] if the double-array cannot grow to
+    /// hold the key.
+    pub fn try_insert(self, data: TrieData) -> DatrieResult<&'a mut TrieData> {
+        let Some(tail) = self
+            .trie
+            .store_at(self.key.as_bytes_with_nul(), self.pos, data, false)
+        else {
+            return Err(DatrieError::new(
+                ErrorKind::Memory,
+                "double-array is full".into(),
+            ));
+        };
+        Ok(self.trie.lend_data(tail).unwrap())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::trie::{AlphaChar, KeyPos, Trie, TrieChar, TrieData, TrieIndex};
+use crate::{AlphaStr, DatrieError, DatrieResult, ErrorKind};
+
+/// The tail block a key ends at, as a compact handle to its entry.
+///
+/// An id stays the same while its key is in the trie, including across
+/// other changes and saving and loading. Once the key is deleted, its id
+/// may be given to a later key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntryId(TrieIndex);
+
+impl EntryId {
+    /// The id with raw value `index`, as returned by [`index`](Self::index).
+    pub fn from_index(index: TrieIndex) -> EntryId {
+        EntryId(index)
+    }
+
+    pub fn index(self) -> TrieIndex {
+        self.0
+    }
+}
+
+impl Trie {
+    /// Store `data` for `key`, returning the id of its entry.
+    ///
+    /// This is [`try_insert`](Self::try_insert) for callers that want the
+    /// id rather than the replaced data; [`insert`](Self::insert) keeps
+    /// returning the replaced data, as map types do. Fails with
+    /// [`ErrorKind::InvalidArgument`] if `key` has characters outside the
+    /// alpha map, and with [`ErrorKind::Memory`] if the double-array cannot
+    /// grow to hold it.
+    pub fn insert_with_id(&mut self, key: &AlphaStr, data: TrieData) -> DatrieResult<EntryId> {
+        let key = self.alpha_map.char_to_trie_str(key).ok_or_else(|| {
+            DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "Trie::insert_with_id: key has characters outside the alpha map".into(),
+            )
+        })?;
+        let key = key.as_bytes_with_nul();
+        let pos = self.locate(key);
+        self.store_at(key, pos, data, true)
+            .map(EntryId)
+            .ok_or_else(|| DatrieError::new(ErrorKind::Memory, "double-array is full".into()))
+    }
+
+    /// The id of the entry for `key`.
+    pub fn lookup_id(&self, key: &AlphaStr) -> Option<EntryId> {
+        let key = self.alpha_map.char_to_trie_str(key)?;
+        match self.locate(key.as_bytes_with_nul()) {
+            KeyPos::Found { tail, .. } => Some(EntryId(tail)),
+            _ => None,
+        }
+    }
+
+    /// The key of entry `id`, or `None` if no key has that id.
+    ///
+    /// The key is rebuilt by following parent links up the double-array
+    /// from the separate node of the entry, then adding its tail suffix.
+    /// The double-array keeps a link from each tail block to its separate
+    /// node; it is built by a walk of the trie on the first call and kept
+    /// current by later changes.
+    pub fn key_of(&self, id: EntryId) -> Option<Vec<AlphaChar>> {
+        let sep = self.da.tail_node(id.0)?;
+        let root = self.da.get_root();
+        let mut key: Vec<TrieChar> = Vec::new();
+        let mut s = sep;
+        while s != root {
+            let parent = self.da.get_check(s);
+            let c = (s - self.da.get_base(parent)) as TrieChar;
+            if c != 0 {
+                key.push(c);
+            }
+            s = parent;
+        }
+        key.reverse();
+        if let Some(suffix) = self.tail.get_suffix(id.0) {
+            key.extend_from_slice(suffix.to_bytes());
+        }
+        Some(self.alpha_key(&key))
+    }
+}
@@ -0,0 +1,167 @@
+use crate::trie::{AlphaChar, Trie, TrieChar, TrieData, TrieIndex};
+use crate::AlphaStr;
+
+/// The edit operations counted by [`Trie::fuzzy_search_by`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EditDistance {
+    /// Substitution, insertion and deletion of one character.
+    #[default]
+    Levenshtein,
+    /// Levenshtein edits, plus transposition of two adjacent characters.
+    Damerau,
+}
+
+/// A key found by [`Trie::fuzzy_search`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// The key, without terminator.
+    pub key: Vec<AlphaChar>,
+    /// Edit distance between the key and the query.
+    pub distance: u32,
+    pub data: TrieData,
+}
+
+impl Trie {
+    /// Find all keys within `max_edits` Levenshtein edits of `query`.
+    ///
+    /// Matches are returned in key order.
+    pub fn fuzzy_search(&self, query: &AlphaStr, max_edits: u32) -> Vec<FuzzyMatch> {
+        self.fuzzy_search_by(query, max_edits, EditDistance::Levenshtein)
+    }
+
+    /// Find all keys within `max_edits` edits of `query`, counting edits
+    /// with `metric`.
+    ///
+    /// The trie is walked node by node with one distance row per key char,
+    /// and subtrees whose row is already past `max_edits` are skipped.
+    pub fn fuzzy_search_by(
+        &self,
+        query: &AlphaStr,
+        max_edits: u32,
+        metric: EditDistance,
+    ) -> Vec<FuzzyMatch> {
+        // query chars outside the alpha map match no trie char
+        let query: Vec<Option<TrieChar>> = query
+            .to_slice()
+            .iter()
+            .map(|&ac| self.alpha_map.char_to_trie(ac).map(|tc| tc as TrieChar))
+            .collect();
+        let first_row = (0..=query.len() as u32).collect();
+        let mut walk = FuzzyWalk {
+            trie: self,
+            query,
+            max_edits,
+            metric,
+            key: Vec::new(),
+            rows: vec![first_row],
+            matches: Vec::new(),
+        };
+        walk.visit(self.da.get_root());
+        walk.matches
+    }
+}
+
+struct FuzzyWalk<'a> {
+    trie: &'a Trie,
+    query: Vec<Option<TrieChar>>,
+    max_edits: u32,
+    metric: EditDistance,
+    /// Trie chars walked so far.
+    key: Vec<TrieChar>,
+    /// `rows[i][j]` is the distance between `key[..i]` and `query[..j]`.
+    rows: Vec<Vec<u32>>,
+    matches: Vec<FuzzyMatch>,
+}
+
+impl FuzzyWalk<'_> {
+    fn visit(&mut self, s: TrieIndex) {
+        let trie = self.trie;
+        let da = &trie.da;
+        let base = da.get_base(s);
+        if base < 0 {
+            self.visit_tail(-base);
+            return;
+        }
+        if base == 0 {
+            return;
+        }
+        let syms = da.output_symbols(s);
+        for i in 0..syms.num() {
+            let c = syms.get(i);
+            if c == 0 {
+                self.visit(base);
+                continue;
+            }
+            if self.push_char(c) {
+                self.visit(base + c as TrieIndex);
+            }
+            self.pop_char();
+        }
+    }
+
+    fn visit_tail(&mut self, t: TrieIndex) {
+        let trie = self.trie;
+        let suffix = trie
+            .tail
+            .get_suffix(t)
+            .map_or(&[][..], |suffix| suffix.to_bytes());
+        let mut pushed = 0;
+        let mut in_reach = true;
+        for &c in suffix {
+            pushed += 1;
+            if !self.push_char(c) {
+                in_reach = false;
+                break;
+            }
+        }
+        let distance = self.rows.last().unwrap()[self.query.len()];
+        if in_reach && distance <= self.max_edits {
+            let key = self
+                .key
+                .iter()
+                .map(|&tc| self.trie.alpha_map.trie_to_char(tc))
+                .collect();
+            self.matches.push(FuzzyMatch {
+                key,
+                distance,
+                data: self.trie.tail.get_data(t).unwrap_or(-1),
+            });
+        }
+        for _ in 0..pushed {
+            self.pop_char();
+        }
+    }
+
+    /// Append `c` to the key and compute its distance row.
+    ///
+    /// Returns `false` if no extension of the key can be within reach.
+    fn push_char(&mut self, c: TrieChar) -> bool {
+        let i = self.rows.len();
+        let prev = &self.rows[i - 1];
+        let mut row = Vec::with_capacity(prev.len());
+        row.push(prev[0] + 1);
+        for j in 1..prev.len() {
+            let cost = u32::from(self.query[j - 1] != Some(c));
+            let mut d = (prev[j - 1] + cost).min(prev[j] + 1).min(row[j - 1] + 1);
+            if self.metric == EditDistance::Damerau
+                && i > 1
+                && j > 1
+                && self.query[j - 1] == Some(self.key[i - 2])
+                && self.query[j - 2] == Some(c)
+            {
+                d = d.min(self.rows[i - 2][j - 2] + 1);
+            }
+            row.push(d);
+        }
+        // no row entry ever drops below the minimum of the row before it
+        let in_reach = row.iter().min().is_some_and(|&d| d <= self.max_edits);
+        self.key.push(c);
+        self.rows.push(row);
+        in_reach
+    }
+
+    fn pop_char(&mut self) {
+        self.key.pop();
+        self.rows.pop();
+    }
+}
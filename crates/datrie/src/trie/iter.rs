@@ -0,0 +1,188 @@
+use crate::trie::cursor::Cursor;
+use crate::trie::range::Range;
+use crate::trie::{AlphaChar, Trie, TrieChar, TrieData};
+use crate::{AlphaStr, DatrieError, DatrieResult, ErrorKind};
+
+/// Iterator over all entries of a trie in key order, see [`Trie::iter`].
+///
+/// Unlike [`TrieIterator`](crate::TrieIterator), it can [`seek`](Iter::seek)
+/// to a key, run from both ends, and hand out a [`ResumeToken`] for
+/// continuing the listing later.
+pub struct Iter<'a> {
+    range: Range<'a>,
+    /// Where the front stands, as given by the last `next` or `seek`.
+    position: ResumeToken,
+}
+
+/// A serializable position of an [`Iter`], see [`Iter::resume_token`].
+///
+/// The token holds a key rather than a node, so it stays meaningful when
+/// the trie is modified or reloaded in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeToken {
+    key: Vec<AlphaChar>,
+    /// Whether `key` itself is still to be listed.
+    inclusive: bool,
+}
+
+impl Trie {
+    /// Iterate over all entries in key order.
+    pub fn iter(&self) -> Iter<'_> {
+        let mut front = Cursor::new(self);
+        front.seek_first();
+        let mut back = Cursor::new(self);
+        back.seek_last();
+        Iter {
+            range: Range::new(front, back),
+            position: ResumeToken::start(),
+        }
+    }
+
+    /// Iterate over the entries whose keys start with `prefix`.
+    ///
+    /// A prefix with characters outside the alpha map gives no entries.
+    pub fn iter_prefix(&self, prefix: &AlphaStr) -> Iter<'_> {
+        let mut front = Cursor::new(self);
+        let mut back = Cursor::new(self);
+        if let Some(prefix) = self.alpha_map.char_to_trie_str(prefix) {
+            let prefix = prefix.to_bytes();
+            front.seek_ge(prefix, true);
+            // keys with the prefix sort before it with its last char
+            // raised, once chars that cannot be raised are dropped
+            let mut upper = prefix.to_vec();
+            while upper.last() == Some(&TrieChar::MAX) {
+                upper.pop();
+            }
+            match upper.last_mut() {
+                Some(c) => {
+                    *c += 1;
+                    back.seek_le(&upper, false);
+                }
+                None => {
+                    back.seek_last();
+                }
+            }
+        }
+        Iter {
+            range: Range::new(front, back),
+            position: ResumeToken::start(),
+        }
+    }
+
+    /// Iterate over the entries from where `token` was taken.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if the token key has
+    /// characters outside the alpha map.
+    pub fn iter_from(&self, token: &ResumeToken) -> DatrieResult<Iter<'_>> {
+        let mut iter = self.iter();
+        iter.seek_to(token)?;
+        Ok(iter)
+    }
+}
+
+impl Iter<'_> {
+    /// Move the front to the first entry with key `>= key`.
+    ///
+    /// The back end is kept, so the iterator may end up empty. Fails with
+    /// [`ErrorKind::InvalidArgument`] if `key` has characters outside the
+    /// alpha map.
+    pub fn seek(&mut self, key: &AlphaStr) -> DatrieResult<()> {
+        self.seek_to(&ResumeToken {
+            key: key.to_slice().to_vec(),
+            inclusive: true,
+        })
+    }
+
+    /// A token for continuing forward iteration with [`Trie::iter_from`].
+    ///
+    /// Entries taken from the back are not recorded.
+    pub fn resume_token(&self) -> ResumeToken {
+        self.position.clone()
+    }
+
+    fn seek_to(&mut self, token: &ResumeToken) -> DatrieResult<()> {
+        let key = self.trie_key(&token.key)?;
+        self.range.seek_front(&key, token.inclusive);
+        self.position = token.clone();
+        Ok(())
+    }
+
+    fn trie_key(&self, key: &[AlphaChar]) -> DatrieResult<Vec<TrieChar>> {
+        let alpha_map = &self.range.trie().alpha_map;
+        key.iter()
+            .map(|&ac| alpha_map.char_to_trie(ac).map(|tc| tc as TrieChar))
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                DatrieError::new(
+                    ErrorKind::InvalidArgument,
+                    "Iter::seek: key has characters outside the alpha map".into(),
+                )
+            })
+    }
+}
+
+impl Iterator for Iter<'_> {
+    type Item = (Vec<AlphaChar>, TrieData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.range.next()?;
+        self.position = ResumeToken {
+            key: entry.0.clone(),
+            inclusive: false,
+        };
+        Some(entry)
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back()
+    }
+}
+
+impl ResumeToken {
+    /// The token of a fresh iterator.
+    pub fn start() -> ResumeToken {
+        ResumeToken {
+            key: Vec::new(),
+            inclusive: true,
+        }
+    }
+
+    /// Serialize as a flag byte followed by the key chars in big-endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + 4 * self.key.len());
+        bytes.push(self.inclusive as u8);
+        for &ac in &self.key {
+            bytes.extend_from_slice(&ac.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Read a token written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if `bytes` is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> DatrieResult<ResumeToken> {
+        let invalid = || {
+            DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "ResumeToken::from_bytes: malformed token".into(),
+            )
+        };
+        let (&flag, chars) = bytes.split_first().ok_or_else(invalid)?;
+        if flag > 1 || chars.len() % 4 != 0 {
+            return Err(invalid());
+        }
+        let key = chars
+            .chunks_exact(4)
+            .map(|c| AlphaChar::from_be_bytes([c[0], c[1], c[2], c[3]]))
+            .collect::<Vec<_>>();
+        if key.contains(&0) {
+            return Err(invalid());
+        }
+        Ok(ResumeToken {
+            key,
+            inclusive: flag == 1,
+        })
+    }
+}
@@ -0,0 +1,208 @@
+use std::cmp::Ordering;
+
+use crate::trie::cursor::Cursor;
+use crate::trie::{AlphaChar, Trie, TrieChar, TrieData};
+use crate::{DatrieError, DatrieResult, ErrorKind};
+
+/// A key met by a [`MergeWalk`], with the data it has on either side.
+pub(crate) enum Merged {
+    Left(Vec<TrieChar>, TrieData),
+    Right(Vec<TrieChar>, TrieData),
+    Both(Vec<TrieChar>, TrieData, TrieData),
+}
+
+/// Walk of the entries of two tries with the same alpha map together, in
+/// key order.
+pub(crate) struct MergeWalk<'a> {
+    left: Cursor<'a>,
+    right: Cursor<'a>,
+}
+
+/// A difference between two tries, see [`Trie::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffEntry {
+    /// A key only in the new trie.
+    Added(Vec<AlphaChar>, TrieData),
+    /// A key only in the old trie.
+    Removed(Vec<AlphaChar>, TrieData),
+    /// A key in both tries, with the old and the new data.
+    Changed(Vec<AlphaChar>, TrieData, TrieData),
+}
+
+/// Iterator over the differences between two tries, see [`Trie::diff`].
+pub struct Diff<'a> {
+    walk: MergeWalk<'a>,
+}
+
+impl<'a> MergeWalk<'a> {
+    pub(crate) fn new(left: &'a Trie, right: &'a Trie) -> MergeWalk<'a> {
+        debug_assert!(left.alpha_map == right.alpha_map);
+        let mut walk = MergeWalk {
+            left: Cursor::new(left),
+            right: Cursor::new(right),
+        };
+        walk.left.seek_first();
+        walk.right.seek_first();
+        walk
+    }
+}
+
+impl Iterator for MergeWalk<'_> {
+    type Item = Merged;
+
+    fn next(&mut self) -> Option<Merged> {
+        let order = match (self.left.is_valid(), self.right.is_valid()) {
+            (false, false) => return None,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (true, true) => self.left.key().cmp(&self.right.key()),
+        };
+        Some(match order {
+            Ordering::Less => {
+                let item = Merged::Left(self.left.key(), self.left.data());
+                self.left.next();
+                item
+            }
+            Ordering::Greater => {
+                let item = Merged::Right(self.right.key(), self.right.data());
+                self.right.next();
+                item
+            }
+            Ordering::Equal => {
+                let item = Merged::Both(self.left.key(), self.left.data(), self.right.data());
+                self.left.next();
+                self.right.next();
+                item
+            }
+        })
+    }
+}
+
+impl Trie {
+    /// `other` remapped to the alpha map of this trie, or `None` if the
+    /// maps are the same.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if
+    /// `other` has keys that this alpha map cannot code.
+    pub(crate) fn remap_other(&self, other: &Trie) -> DatrieResult<Option<Trie>> {
+        if self.alpha_map == other.alpha_map {
+            Ok(None)
+        } else {
+            other.remap_alphabet(&self.alpha_map).map(Some)
+        }
+    }
+
+    pub(crate) fn alpha_key(&self, key: &[TrieChar]) -> Vec<AlphaChar> {
+        key.iter()
+            .map(|&tc| self.alpha_map.trie_to_char(tc))
+            .collect()
+    }
+
+    /// The differences from this trie to `other`, in key order.
+    ///
+    /// Both tries are walked together once, so this takes time linear in
+    /// their sizes. Fails with [`ErrorKind::InvalidArgument`] if the alpha
+    /// maps differ; remap one with [`remap_alphabet`](Self::remap_alphabet)
+    /// first.
+    pub fn diff<'a>(&'a self, other: &'a Trie) -> DatrieResult<Diff<'a>> {
+        if self.alpha_map != other.alpha_map {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "Trie::diff: tries have different alpha maps".into(),
+            ));
+        }
+        Ok(Diff {
+            walk: MergeWalk::new(self, other),
+        })
+    }
+
+    /// Store all entries of `other` in this trie.
+    ///
+    /// For keys in both tries, the data becomes `resolve(key, ours,
+    /// theirs)`. If the alpha maps differ, `other` is remapped first; this
+    /// fails with [`ErrorKind::InvalidArgument`] if it has
+    /// keys that this alpha map cannot code, leaving this trie unchanged.
+    /// It fails with [`ErrorKind::Memory`] if the double-array cannot grow
+    /// to hold a new key, after the updates before it have been applied.
+    pub fn merge_from<F>(&mut self, other: &Trie, resolve: F) -> DatrieResult<()>
+    where
+        F: Fn(&[AlphaChar], TrieData, TrieData) -> TrieData,
+    {
+        let remapped = self.remap_other(other)?;
+        let other = remapped.as_ref().unwrap_or(other);
+        let updates: Vec<(Vec<TrieChar>, TrieData)> = MergeWalk::new(self, other)
+            .filter_map(|merged| match merged {
+                Merged::Left(..) => None,
+                Merged::Right(key, data) => Some((key, data)),
+                Merged::Both(key, ours, theirs) => {
+                    let data = resolve(&self.alpha_key(&key), ours, theirs);
+                    (data != ours).then_some((key, data))
+                }
+            })
+            .collect();
+        for (mut key, data) in updates {
+            key.push(0);
+            self.insert_trie_key(&key, data)?;
+        }
+        Ok(())
+    }
+
+    /// A new trie with the entries of this trie whose keys are also in
+    /// `other`.
+    ///
+    /// Alpha maps are handled as in [`merge_from`](Self::merge_from); the
+    /// result has the alpha map of this trie.
+    pub fn intersection(&self, other: &Trie) -> DatrieResult<Trie> {
+        self.merge_into_new(other, |merged| match merged {
+            Merged::Both(key, ours, _) => Some((key, ours)),
+            _ => None,
+        })
+    }
+
+    /// A new trie with the entries of this trie whose keys are not in
+    /// `other`.
+    pub fn difference(&self, other: &Trie) -> DatrieResult<Trie> {
+        self.merge_into_new(other, |merged| match merged {
+            Merged::Left(key, data) => Some((key, data)),
+            _ => None,
+        })
+    }
+
+    /// A new trie with the entries of either trie whose keys are not in the
+    /// other.
+    pub fn symmetric_difference(&self, other: &Trie) -> DatrieResult<Trie> {
+        self.merge_into_new(other, |merged| match merged {
+            Merged::Left(key, data) | Merged::Right(key, data) => Some((key, data)),
+            Merged::Both(..) => None,
+        })
+    }
+
+    fn merge_into_new<F>(&self, other: &Trie, select: F) -> DatrieResult<Trie>
+    where
+        F: Fn(Merged) -> Option<(Vec<TrieChar>, TrieData)>,
+    {
+        let remapped = self.remap_other(other)?;
+        let other = remapped.as_ref().unwrap_or(other);
+        let mut trie = Trie::new(&self.alpha_map)?;
+        for (mut key, data) in MergeWalk::new(self, other).filter_map(select) {
+            key.push(0);
+            trie.insert_trie_key(&key, data)?;
+        }
+        Ok(trie)
+    }
+}
+
+impl Iterator for Diff<'_> {
+    type Item = DiffEntry;
+
+    fn next(&mut self) -> Option<DiffEntry> {
+        let trie = self.walk.left.trie();
+        self.walk.find_map(|merged| match merged {
+            Merged::Left(key, data) => Some(DiffEntry::Removed(trie.alpha_key(&key), data)),
+            Merged::Right(key, data) => Some(DiffEntry::Added(trie.alpha_key(&key), data)),
+            Merged::Both(key, old, new) => {
+                (old != new).then(|| DiffEntry::Changed(trie.alpha_key(&key), old, new))
+            }
+        })
+    }
+}
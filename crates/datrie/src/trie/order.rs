@@ -0,0 +1,105 @@
+use crate::trie::cursor::Cursor;
+use crate::trie::{AlphaChar, KeyPos, Trie, TrieChar, TrieData, TrieIndex};
+use crate::AlphaStr;
+
+impl Trie {
+    /// The `n`-th entry in iteration order, counting from 0.
+    ///
+    /// With key counting on, see [`set_key_counting`](Self::set_key_counting),
+    /// this takes O(key length · alphabet size). Otherwise the subtrees
+    /// skipped over are walked to count their keys.
+    pub fn nth(&self, mut n: usize) -> Option<(Vec<AlphaChar>, TrieData)> {
+        let mut s = self.da.get_root();
+        let mut key: Vec<TrieChar> = Vec::new();
+        'descend: loop {
+            let base = self.da.get_base(s);
+            if base < 0 {
+                if n > 0 {
+                    return None;
+                }
+                let t = -base;
+                if let Some(suffix) = self.tail.get_suffix(t) {
+                    key.extend_from_slice(suffix.to_bytes());
+                }
+                let alpha_key = key
+                    .iter()
+                    .map(|&tc| self.alpha_map.trie_to_char(tc))
+                    .collect();
+                return Some((alpha_key, self.tail.get_data(t).unwrap_or(-1)));
+            }
+            if base == 0 {
+                return None;
+            }
+            let syms = self.da.output_symbols(s);
+            for i in 0..syms.num() {
+                let c = syms.get(i);
+                let child = base + c as TrieIndex;
+                let count = self.count_below(child);
+                if n < count {
+                    if c != 0 {
+                        key.push(c);
+                    }
+                    s = child;
+                    continue 'descend;
+                }
+                n -= count;
+            }
+            return None;
+        }
+    }
+
+    /// Position of `key` in iteration order, or `None` if it is not in the
+    /// trie.
+    ///
+    /// This is the inverse of [`nth`](Self::nth), at the same cost.
+    pub fn rank(&self, key: &AlphaStr) -> Option<usize> {
+        let key = self.alpha_map.char_to_trie_str(key)?;
+        let key = key.as_bytes_with_nul();
+        let KeyPos::Found { sep, .. } = self.locate(key) else {
+            return None;
+        };
+        let mut rank = 0;
+        let mut s = self.da.get_root();
+        for &tc in key {
+            if s == sep {
+                break;
+            }
+            let base = self.da.get_base(s);
+            let syms = self.da.output_symbols(s);
+            for i in 0..syms.num() {
+                let c = syms.get(i);
+                if c >= tc {
+                    break;
+                }
+                rank += self.count_below(base + c as TrieIndex);
+            }
+            s = base + tc as TrieIndex;
+        }
+        Some(rank)
+    }
+
+    /// The first entry with key after `key`, which need not be stored.
+    ///
+    /// The key is walked down the double-array and compared with the tail
+    /// suffix where it leaves the trie, then the walk backtracks to the next
+    /// sibling symbol. Returns `None` if `key` has characters outside the
+    /// alpha map.
+    pub fn successor(&self, key: &AlphaStr) -> Option<(Vec<AlphaChar>, TrieData)> {
+        let key = self.alpha_map.char_to_trie_str(key)?;
+        let mut cursor = Cursor::new(self);
+        cursor
+            .seek_ge(key.to_bytes(), false)
+            .then(|| cursor.entry())
+    }
+
+    /// The last entry with key before `key`, which need not be stored.
+    ///
+    /// See [`successor`](Self::successor).
+    pub fn predecessor(&self, key: &AlphaStr) -> Option<(Vec<AlphaChar>, TrieData)> {
+        let key = self.alpha_map.char_to_trie_str(key)?;
+        let mut cursor = Cursor::new(self);
+        cursor
+            .seek_le(key.to_bytes(), false)
+            .then(|| cursor.entry())
+    }
+}
@@ -0,0 +1,179 @@
+use std::str::FromStr;
+
+use crate::trie::{AlphaChar, Automaton, Trie, TrieData};
+use crate::{DatrieError, DatrieResult, ErrorKind};
+
+/// A wildcard pattern over keys, see [`Trie::match_pattern`].
+///
+/// The pattern syntax is:
+///
+/// - `?` matches any one character,
+/// - `*` matches any run of characters, including none,
+/// - `[abc]` and `[a-z]` match one character of a class, and `[!abc]` or
+///   `[^abc]` one character outside it,
+/// - `\` makes the next character literal,
+/// - every other character matches itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    tokens: Vec<PatternToken>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternToken {
+    Char(AlphaChar),
+    AnyChar,
+    AnySeq,
+    Class {
+        ranges: Vec<(AlphaChar, AlphaChar)>,
+        negated: bool,
+    },
+}
+
+impl PatternToken {
+    fn matches(&self, ac: AlphaChar) -> bool {
+        match self {
+            PatternToken::Char(c) => *c == ac,
+            PatternToken::AnyChar | PatternToken::AnySeq => true,
+            PatternToken::Class { ranges, negated } => {
+                ranges.iter().any(|&(lo, hi)| lo <= ac && ac <= hi) != *negated
+            }
+        }
+    }
+}
+
+impl Pattern {
+    /// Parse a pattern from its characters.
+    pub fn parse(pattern: &[AlphaChar]) -> DatrieResult<Pattern> {
+        let mut tokens = Vec::new();
+        let mut chars = pattern.iter().copied();
+        while let Some(ac) = chars.next() {
+            let token = match char::from_u32(ac) {
+                Some('?') => PatternToken::AnyChar,
+                Some('*') => PatternToken::AnySeq,
+                Some('[') => Self::parse_class(&mut chars)?,
+                Some('\\') => PatternToken::Char(chars.next().ok_or_else(|| {
+                    DatrieError::new(
+                        ErrorKind::InvalidArgument,
+                        "Pattern::parse: pattern ends with an escape".into(),
+                    )
+                })?),
+                _ => PatternToken::Char(ac),
+            };
+            tokens.push(token);
+        }
+        Ok(Pattern { tokens })
+    }
+
+    fn parse_class(chars: &mut impl Iterator<Item = AlphaChar>) -> DatrieResult<PatternToken> {
+        let unterminated = || {
+            DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "Pattern::parse: unterminated character class".into(),
+            )
+        };
+        let is = |ac: AlphaChar, c: char| ac == c as AlphaChar;
+        let mut next = || chars.next().ok_or_else(unterminated);
+        let mut ranges = Vec::new();
+        let mut negated = false;
+        let mut ac = next()?;
+        if is(ac, '!') || is(ac, '^') {
+            negated = true;
+            ac = next()?;
+        }
+        // a ']' right after the opening bracket is literal
+        let mut first = true;
+        while first || !is(ac, ']') {
+            first = false;
+            let lo = if is(ac, '\\') { next()? } else { ac };
+            ac = next()?;
+            if !is(ac, '-') {
+                ranges.push((lo, lo));
+                continue;
+            }
+            let hi = next()?;
+            if is(hi, ']') {
+                // a trailing '-' is literal
+                ranges.extend([(lo, lo), (ac, ac)]);
+                break;
+            }
+            let hi = if is(hi, '\\') { next()? } else { hi };
+            ranges.push((lo, hi));
+            ac = next()?;
+        }
+        Ok(PatternToken::Class { ranges, negated })
+    }
+
+    /// Add the positions reachable from `pos` without consuming a
+    /// character to the sorted set `states`.
+    fn add_state(&self, states: &mut Vec<usize>, mut pos: usize) {
+        loop {
+            if let Err(i) = states.binary_search(&pos) {
+                states.insert(i, pos);
+            }
+            match self.tokens.get(pos) {
+                Some(PatternToken::AnySeq) => pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// The states after matching `ac` from `states`.
+    fn step(&self, states: &[usize], ac: AlphaChar) -> Vec<usize> {
+        let mut next = Vec::new();
+        for &pos in states {
+            match self.tokens.get(pos) {
+                Some(PatternToken::AnySeq) => self.add_state(&mut next, pos),
+                Some(token) if token.matches(ac) => self.add_state(&mut next, pos + 1),
+                _ => {}
+            }
+        }
+        next
+    }
+
+    fn is_accepting(&self, states: &[usize]) -> bool {
+        states.last() == Some(&self.tokens.len())
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = DatrieError;
+
+    fn from_str(s: &str) -> DatrieResult<Pattern> {
+        let chars: Vec<AlphaChar> = s.chars().map(|c| c as AlphaChar).collect();
+        Pattern::parse(&chars)
+    }
+}
+
+impl Automaton for Pattern {
+    /// The sorted set of pattern positions reached.
+    type State = Vec<usize>;
+
+    fn start(&self) -> Vec<usize> {
+        let mut states = Vec::new();
+        self.add_state(&mut states, 0);
+        states
+    }
+
+    fn accept(&self, states: &Vec<usize>, ac: AlphaChar) -> Vec<usize> {
+        self.step(states, ac)
+    }
+
+    fn is_match(&self, states: &Vec<usize>) -> bool {
+        self.is_accepting(states)
+    }
+
+    fn can_match(&self, states: &Vec<usize>) -> bool {
+        !states.is_empty()
+    }
+}
+
+impl Trie {
+    /// Find all keys that match `pattern`, with their data, in key order.
+    ///
+    /// Only the children that the double-array lists for a node are
+    /// followed, and a branch is left as soon as no part of the pattern can
+    /// match it.
+    pub fn match_pattern(&self, pattern: &Pattern) -> Vec<(Vec<AlphaChar>, TrieData)> {
+        self.search(pattern).collect()
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::trie::cursor::Cursor;
+use crate::trie::{AlphaChar, Trie, TrieChar, TrieData};
+use crate::{AlphaStr, DatrieError, DatrieResult, ErrorKind};
+
+/// Iterator over the entries of a key range, see [`Trie::range`].
+pub struct Range<'a> {
+    front: Cursor<'a>,
+    back: Cursor<'a>,
+    /// Whether the cursors have met, so the range is used up.
+    done: bool,
+}
+
+impl Trie {
+    /// Iterate over the entries whose keys fall in `range`, in key order.
+    ///
+    /// Keys are ordered as in the alpha map, with a key sorting before its
+    /// extensions. The iterator seeks straight to the ends of the range, and
+    /// can be run from both ends.
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] if a bound has characters
+    /// outside the alpha map.
+    pub fn range<'k, R: RangeBounds<&'k AlphaStr>>(&self, range: R) -> DatrieResult<Range<'_>> {
+        let start = self.trie_bound(range.start_bound())?;
+        let end = self.trie_bound(range.end_bound())?;
+        let mut front = Cursor::new(self);
+        match &start {
+            Bound::Included(key) => front.seek_ge(key, true),
+            Bound::Excluded(key) => front.seek_ge(key, false),
+            Bound::Unbounded => front.seek_first(),
+        };
+        let mut back = Cursor::new(self);
+        match &end {
+            Bound::Included(key) => back.seek_le(key, true),
+            Bound::Excluded(key) => back.seek_le(key, false),
+            Bound::Unbounded => back.seek_last(),
+        };
+        Ok(Range::new(front, back))
+    }
+
+    fn trie_bound(&self, bound: Bound<&&AlphaStr>) -> DatrieResult<Bound<Vec<TrieChar>>> {
+        let to_trie = |key: &AlphaStr| {
+            self.alpha_map
+                .char_to_trie_str(key)
+                .map(|key| key.to_bytes().to_vec())
+                .ok_or_else(|| {
+                    DatrieError::new(
+                        ErrorKind::InvalidArgument,
+                        "Trie::range: bound has characters outside the alpha map".into(),
+                    )
+                })
+        };
+        Ok(match bound {
+            Bound::Included(key) => Bound::Included(to_trie(key)?),
+            Bound::Excluded(key) => Bound::Excluded(to_trie(key)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    }
+}
+
+impl<'a> Range<'a> {
+    /// The entries from `front` to `back`, both included.
+    pub(crate) fn new(front: Cursor<'a>, back: Cursor<'a>) -> Range<'a> {
+        let mut range = Range {
+            front,
+            back,
+            done: false,
+        };
+        range.check_order();
+        range
+    }
+
+    pub(crate) fn trie(&self) -> &'a Trie {
+        self.front.trie()
+    }
+
+    /// Move the front to the first entry with key `>= key` (or `> key`, if
+    /// not `inclusive`), keeping the back.
+    pub(crate) fn seek_front(&mut self, key: &[TrieChar], inclusive: bool) {
+        self.front.seek_ge(key, inclusive);
+        self.done = false;
+        self.check_order();
+    }
+
+    fn check_order(&mut self) {
+        if !self.front.is_valid()
+            || !self.back.is_valid()
+            || self.front.cmp_entry(&self.back).is_gt()
+        {
+            self.done = true;
+        }
+    }
+
+    /// Note that the cursors meet, if the entry just taken was the last.
+    fn check_done(&mut self) {
+        if self.front.same_entry(&self.back) {
+            self.done = true;
+        }
+    }
+}
+
+impl Iterator for Range<'_> {
+    type Item = (Vec<AlphaChar>, TrieData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let entry = self.front.entry();
+        self.check_done();
+        self.front.next();
+        Some(entry)
+    }
+}
+
+impl DoubleEndedIterator for Range<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let entry = self.back.entry();
+        self.check_done();
+        self.back.prev();
+        Some(entry)
+    }
+}
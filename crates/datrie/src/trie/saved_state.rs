@@ -0,0 +1,129 @@
+use std::io;
+
+use crate::alpha_map::{DA_FALSE, DA_TRUE};
+use crate::trie::{Trie, TrieIndex, TrieState};
+use crate::{DatrieError, DatrieResult, ErrorKind};
+
+/// Size of a saved state: fingerprint, index, suffix index and suffix flag.
+const SAVED_STATE_SIZE: usize = 8 + 4 + 4 + 1;
+
+impl Trie {
+    /// A hash of the whole trie, for telling whether saved data still
+    /// matches it.
+    ///
+    /// This is the 64-bit FNV-1a hash of the serialized trie, so it is the
+    /// same for a trie after saving and loading. Computing it takes time
+    /// linear in the size of the trie. It is computed on the first call and
+    /// kept until the trie is next modified, data changes included.
+    pub fn fingerprint(&self) -> u64 {
+        *self.fingerprint.get_or_init(|| {
+            let mut hasher = Fnv1a::new();
+            // writing to the hasher cannot fail
+            self.alpha_map.serialize(&mut hasher).unwrap();
+            self.da.serialize(&mut hasher).unwrap();
+            self.tail.serialize(&mut hasher).unwrap();
+            hasher.0
+        })
+    }
+
+    /// Save walker `state` of this trie as bytes, so the walk can continue
+    /// later with [`load_state`](Self::load_state).
+    ///
+    /// The saved state holds the [`fingerprint`](Self::fingerprint) of the
+    /// trie, which is recomputed if the trie changed since it was last
+    /// taken. Fails with [`ErrorKind::InvalidArgument`] if `state` belongs
+    /// to another trie.
+    pub fn save_state(&self, state: &TrieState) -> DatrieResult<Vec<u8>> {
+        if !std::ptr::eq(state.trie, self) {
+            return Err(DatrieError::new(
+                ErrorKind::InvalidArgument,
+                "Trie::save_state: state belongs to another trie".into(),
+            ));
+        }
+        // the suffix index is left stale by a rewind, so keep it only in
+        // the tail
+        let is_suffix = state.is_suffix != 0;
+        let suffix_idx = if is_suffix { state.suffix_idx } else { 0 };
+        let mut bytes = Vec::with_capacity(SAVED_STATE_SIZE);
+        bytes.extend_from_slice(&self.fingerprint().to_be_bytes());
+        bytes.extend_from_slice(&state.index.to_be_bytes());
+        bytes.extend_from_slice(&(suffix_idx as u32).to_be_bytes());
+        bytes.push(is_suffix as u8);
+        Ok(bytes)
+    }
+
+    /// Load a walker state saved by [`save_state`](Self::save_state).
+    ///
+    /// The state points into this trie, so it must not outlive it. Fails
+    /// with [`ErrorKind::InvalidArgument`] if `bytes` is malformed, was
+    /// saved from a different or since modified trie, or does not point to
+    /// a valid position. Checking the trie takes its
+    /// [`fingerprint`](Self::fingerprint), as saving does.
+    pub fn load_state(&self, bytes: &[u8]) -> DatrieResult<TrieState> {
+        let invalid = |msg: &str| {
+            DatrieError::new(
+                ErrorKind::InvalidArgument,
+                format!("Trie::load_state: {}", msg),
+            )
+        };
+        if bytes.len() != SAVED_STATE_SIZE {
+            return Err(invalid("wrong size"));
+        }
+        let fingerprint = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+        if fingerprint != self.fingerprint() {
+            return Err(invalid("state was saved from another trie"));
+        }
+        let index = TrieIndex::from_be_bytes(bytes[8..12].try_into().unwrap());
+        let suffix_idx = u32::from_be_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let is_suffix = match bytes[16] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid("bad suffix flag")),
+        };
+        let is_valid = if is_suffix {
+            // free tail blocks have no separate node pointing to them
+            self.da.tail_node(index).is_some()
+                && self
+                    .tail
+                    .get_suffix(index)
+                    .is_some_and(|suffix| suffix_idx <= suffix.to_bytes().len())
+        } else {
+            let root = self.da.get_root();
+            index > 0
+                && (index as usize) < self.da.num_cells()
+                && suffix_idx == 0
+                && (index == root || self.da.get_check(index) > 0 && self.da.get_base(index) > 0)
+        };
+        if !is_valid {
+            return Err(invalid("state is not a position in the trie"));
+        }
+        Ok(TrieState {
+            trie: self,
+            index,
+            suffix_idx,
+            is_suffix: (if is_suffix { DA_TRUE } else { DA_FALSE }) as libc::c_short,
+        })
+    }
+}
+
+/// A 64-bit FNV-1a hasher fed through [`io::Write`].
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Fnv1a {
+        Fnv1a(0xcbf29ce484222325)
+    }
+}
+
+impl io::Write for Fnv1a {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &b in buf {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x100000001b3);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -60,19 +60,106 @@ impl Tail {
 
 impl Tail {
     pub fn fread_safe<R: ReadExt + io::Seek>(reader: &mut R) -> DatrieResult<Tail> {
+        Tail::fread_with(reader, Self::SIGNATURE, |reader| {
+            let mut data = 0;
+            reader.read_int32(&mut data)?;
+            Ok(data)
+        })
+    }
+}
+
+impl Tail {
+    pub fn num_tails(&self) -> usize {
+        self.tails.len()
+    }
+
+    pub fn get_serialized_size(&self) -> usize {
+        self.serialized_size_with(|_| size_of::<TrieData>())
+    }
+
+    /// Magic number signature for the Tail binary format (0xdffcdffc)
+    /// Introduced in the initial binary serialization format
+    const SIGNATURE: u32 = 0xdffcdffc;
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> DatrieResult<usize> {
+        self.serialize_with(&mut writer, Self::SIGNATURE, |writer, data| {
+            writer.write_i32::<BigEndian>(data)?;
+            Ok(size_of::<TrieData>())
+        })
+    }
+}
+
+impl Tail {
+    /// Write the tail in the layout of the plain format, with `signature`
+    /// and the data of each block written by `write_data`.
+    ///
+    /// The plain format writes each data as an `i32`; variants of it use
+    /// their own signature and data encoding.
+    pub(crate) fn serialize_with<W, F>(
+        &self,
+        writer: &mut W,
+        signature: u32,
+        mut write_data: F,
+    ) -> DatrieResult<usize>
+    where
+        W: io::Write,
+        F: FnMut(&mut W, TrieData) -> DatrieResult<usize>,
+    {
+        writer.write_u32::<BigEndian>(signature)?;
+        writer.write_i32::<BigEndian>(self.first_free)?;
+        writer.write_i32::<BigEndian>(self.num_tails() as i32)?;
+        let mut written = 12;
+        for block in &self.tails {
+            writer.write_i32::<BigEndian>(block.next_free)?;
+            written += 4 + write_data(writer, block.data)?;
+            let suffix = block.suffix.as_bytes();
+            writer.write_i16::<BigEndian>(suffix.len() as i16)?;
+            writer.write_all(suffix)?;
+            written += 2 + suffix.len();
+        }
+        Ok(written)
+    }
+
+    /// Size of [`serialize_with`](Self::serialize_with) output, given the
+    /// size `data_size` takes for the data of each block.
+    pub(crate) fn serialized_size_with<F: Fn(TrieData) -> usize>(&self, data_size: F) -> usize {
+        let blocks: usize = self
+            .tails
+            .iter()
+            .map(|block| 6 + data_size(block.data) + block.suffix.as_bytes().len())
+            .sum();
+        12 + blocks
+    }
+
+    /// Read a tail written by [`serialize_with`](Self::serialize_with),
+    /// with `read_data` reading the data of each block.
+    ///
+    /// On failure the reader is moved back to where it was.
+    pub(crate) fn fread_with<R, F>(
+        reader: &mut R,
+        signature: u32,
+        read_data: F,
+    ) -> DatrieResult<Tail>
+    where
+        R: ReadExt + io::Seek,
+        F: FnMut(&mut R) -> DatrieResult<TrieData>,
+    {
         let save_pos = reader.stream_position()?;
-        Tail::do_fread_safe(reader).map_err(|err| {
+        Tail::do_fread_with(reader, signature, read_data).map_err(|err| {
             if let Err(io_err) = reader.seek(SeekFrom::Start(save_pos)) {
                 return io_err.into();
             }
             err
         })
     }
-    fn do_fread_safe<R: ReadExt>(reader: &mut R) -> DatrieResult<Tail> {
-        let current_block: u64;
+
+    fn do_fread_with<R, F>(reader: &mut R, signature: u32, mut read_data: F) -> DatrieResult<Tail>
+    where
+        R: ReadExt,
+        F: FnMut(&mut R) -> DatrieResult<TrieData>,
+    {
         let mut sig: u32 = 0;
         reader.read_uint32(&mut sig)?;
-        if sig != Self::SIGNATURE {
+        if sig != signature {
             return Err(DatrieError::new(
                 crate::ErrorKind::InvalidFileSignature,
                 format!("tail: unexpected signature '{}'", sig),
@@ -82,93 +169,33 @@ impl Tail {
         let mut num_tails = 0;
         reader.read_int32(&mut first_free)?;
         reader.read_int32(&mut num_tails)?;
-        if num_tails as u64
-            > (18446744073709551615u64).wrapping_div(::core::mem::size_of::<TailBlock>() as u64)
-        {
+        if num_tails < 0 {
             return Err(DatrieError::new(
                 crate::ErrorKind::Bug,
-                "failed to read tail: num_tails too large".into(),
+                "failed to read tail: negative num_tails".into(),
             ));
         }
-        let mut tails = Vec::with_capacity(num_tails as usize);
-        let mut i = 0 as libc::c_int;
-        loop {
-            if i >= num_tails {
-                current_block = 15904375183555213903;
-                break;
-            }
-            let mut length: i16 = 0;
+        let mut tails = Vec::new();
+        for _ in 0..num_tails {
             let mut next_free = 0;
-            let mut data = 0;
-            if reader.read_int32(&mut next_free).is_err()
-                || reader.read_int32(&mut data).is_err()
-                || reader.read_int16(&mut length).is_err()
-            {
-                current_block = 1386273818809128762;
-                break;
-            }
-            let mut suffix_data = vec![0; length as usize];
-            if length as libc::c_int > 0 as libc::c_int
-                && reader.read_exact(&mut suffix_data).is_err()
-            {
-                current_block = 1386273818809128762;
-                break;
-            }
+            let mut length: i16 = 0;
+            reader.read_int32(&mut next_free)?;
+            let data = read_data(reader)?;
+            reader.read_int16(&mut length)?;
+            let mut suffix = vec![0; length.max(0) as usize];
+            reader.read_exact(&mut suffix)?;
             tails.push(TailBlock {
                 next_free,
                 data,
-                suffix: TrieCharString::new(suffix_data).unwrap(),
+                suffix: TrieCharString::new(suffix).map_err(|_| {
+                    DatrieError::new(
+                        crate::ErrorKind::Bug,
+                        "failed to read tail: suffix has a nul char".into(),
+                    )
+                })?,
             });
-            i += 1;
-        }
-        if let 15904375183555213903 = current_block {
-            return Ok(Tail { first_free, tails });
         }
-        Err(DatrieError::new(
-            crate::ErrorKind::Bug,
-            "failed to read tail".into(),
-        ))
-    }
-}
-
-impl Tail {
-    pub fn num_tails(&self) -> usize {
-        self.tails.len()
-    }
-
-    pub fn get_serialized_size(&self) -> usize {
-        let static_count = ::core::mem::size_of::<i32>() + 2 * ::core::mem::size_of::<TrieIndex>();
-        let mut dynamic_count: usize = 0;
-        if self.num_tails() > 0 {
-            dynamic_count += (size_of::<TrieIndex>() + size_of::<TrieData>() + size_of::<i16>())
-                * self.num_tails();
-            for i in 0..(self.num_tails()) {
-                dynamic_count += self.tails[i].suffix.as_bytes().len();
-            }
-        }
-        static_count + dynamic_count
-    }
-
-    /// Magic number signature for the Tail binary format (0xdffcdffc)
-    /// Introduced in the initial binary serialization format
-    const SIGNATURE: u32 = 0xdffcdffc;
-    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> DatrieResult<usize> {
-        writer.write_u32::<BigEndian>(Self::SIGNATURE)?;
-        writer.write_i32::<BigEndian>(self.first_free)?;
-        writer.write_i32::<BigEndian>(self.num_tails() as i32)?;
-        let mut written = 12;
-        for i in 0..self.num_tails() {
-            let next_free = self.tails[i].next_free;
-            let data = self.tails[i].data;
-            writer.write_i32::<BigEndian>(next_free)?;
-            writer.write_i32::<BigEndian>(data)?;
-            let length = self.tails[i].suffix.as_bytes().len();
-            writer.write_i16::<BigEndian>(length as i16)?;
-            written += 10;
-            writer.write_all(self.tails[i].suffix.as_bytes())?;
-            written += length;
-        }
-        Ok(written)
+        Ok(Tail { first_free, tails })
     }
 }
 
@@ -251,6 +278,11 @@ impl Tail {
         }
     }
 
+    pub fn get_data_mut(&mut self, index: TrieIndex) -> Option<&mut TrieData> {
+        let index = index as usize - TAIL_START_BLOCKNO;
+        self.tails.get_mut(index).map(|block| &mut block.data)
+    }
+
     pub fn set_data(&mut self, index: TrieIndex, data: TrieData) -> bool {
         let index = index as usize - TAIL_START_BLOCKNO;
         if index < self.num_tails() {
@@ -265,6 +297,31 @@ impl Tail {
         self.free_block(index);
     }
 
+    /// Delete the blocks `indices`, sorted ascending, in one pass over the
+    /// free list.
+    pub(crate) fn delete_many(&mut self, indices: &[TrieIndex]) {
+        let mut j = 0;
+        let mut i = self.first_free;
+        for &index in indices {
+            let block = index - TAIL_START_BLOCKNO as TrieIndex;
+            if block as usize >= self.num_tails() {
+                continue;
+            }
+            self.tails[block as usize].reset();
+            while i != 0 && i < block {
+                j = i;
+                i = self.tails[i as usize].next_free;
+            }
+            self.tails[block as usize].next_free = i;
+            if 0 != j {
+                self.tails[j as usize].next_free = block;
+            } else {
+                self.first_free = block;
+            }
+            j = block;
+        }
+    }
+
     fn walk_str(&self, index: TrieIndex, suffix_idx: &mut usize, s: &[TrieChar]) -> usize {
         let mut i = 0;
         let mut j = *suffix_idx;
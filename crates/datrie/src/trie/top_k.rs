@@ -0,0 +1,169 @@
+use std::cell::Cell;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::trie::{AlphaChar, PrefixPos, Trie, TrieChar, TrieData, TrieIndex};
+use crate::AlphaStr;
+
+impl Trie {
+    /// The `k` completions of `prefix` with the highest `score`, best first.
+    ///
+    /// Completions with equal scores come in key order. `score` must not
+    /// decrease as the data grows, e.g. the data itself for stored
+    /// frequencies, or a bucketing of it: the search is best-first over the
+    /// largest data below each node, and only enters subtrees that can
+    /// still make the top `k`. Results for other scores are unspecified.
+    ///
+    /// The largest data below each node is computed by the first query, and
+    /// kept current as keys are stored and deleted.
+    pub fn top_k_completions<S, F>(
+        &self,
+        prefix: &AlphaStr,
+        k: usize,
+        score: F,
+    ) -> Vec<(Vec<AlphaChar>, TrieData)>
+    where
+        S: Ord,
+        F: Fn(TrieData) -> S,
+    {
+        let Some(prefix) = self.alpha_map.char_to_trie_str(prefix) else {
+            return Vec::new();
+        };
+        let prefix = prefix.to_bytes();
+        if k == 0 {
+            return Vec::new();
+        }
+        let node = match self.locate_prefix(prefix) {
+            None => return Vec::new(),
+            Some(PrefixPos::Node(s)) => s,
+            Some(PrefixPos::InTail { sep, depth }) => {
+                let mut key = prefix[..depth].to_vec();
+                return vec![self.tail_entry(sep, &mut key)];
+            }
+        };
+
+        let max_data = self.max_data();
+        let mut heap = BinaryHeap::new();
+        heap.push(Candidate {
+            score: score(max_data[node as usize].get()),
+            key: prefix.to_vec(),
+            node,
+            is_entry: false,
+        });
+        let mut completions = Vec::with_capacity(k);
+        while let Some(candidate) = heap.pop() {
+            let Candidate {
+                mut key,
+                node: s,
+                is_entry,
+                ..
+            } = candidate;
+            let base = self.da.get_base(s);
+            if is_entry {
+                completions.push(self.tail_entry(s, &mut key));
+                if completions.len() == k {
+                    break;
+                }
+            } else if base < 0 {
+                // the single key below a separate node is its own candidate
+                heap.push(Candidate {
+                    score: score(max_data[s as usize].get()),
+                    key,
+                    node: s,
+                    is_entry: true,
+                });
+            } else if base > 0 {
+                let syms = self.da.output_symbols(s);
+                for i in 0..syms.num() {
+                    let c = syms.get(i);
+                    let child = base + c as TrieIndex;
+                    let mut child_key = key.clone();
+                    if c != 0 {
+                        child_key.push(c);
+                    }
+                    heap.push(Candidate {
+                        score: score(max_data[child as usize].get()),
+                        key: child_key,
+                        node: child,
+                        is_entry: false,
+                    });
+                }
+            }
+        }
+        completions
+    }
+
+    /// The `k` completions of `prefix` with the largest data, largest
+    /// first.
+    ///
+    /// This is [`top_k_completions`](Self::top_k_completions) scored by the
+    /// data itself.
+    pub fn top_k_by_data(&self, prefix: &AlphaStr, k: usize) -> Vec<(Vec<AlphaChar>, TrieData)> {
+        self.top_k_completions(prefix, k, |data| data)
+    }
+
+    /// The key and data of separate node `sep`, whose key starts with the
+    /// trie chars in `key`.
+    fn tail_entry(&self, sep: TrieIndex, key: &mut Vec<TrieChar>) -> (Vec<AlphaChar>, TrieData) {
+        let t = -self.da.get_base(sep);
+        if let Some(suffix) = self.tail.get_suffix(t) {
+            key.extend_from_slice(suffix.to_bytes());
+        }
+        let alpha_key = key
+            .iter()
+            .map(|&tc| self.alpha_map.trie_to_char(tc))
+            .collect();
+        (alpha_key, self.tail.get_data(t).unwrap_or(-1))
+    }
+
+    /// The largest data below each DArray node, indexed by node, with the
+    /// data lent out since the last query taken into account.
+    fn max_data(&self) -> &[Cell<TrieData>] {
+        let data_of = |t| self.tail.get_data(t).unwrap_or(-1);
+        let max_data = self.da.max_data(data_of);
+        for t in self.lent_data.take() {
+            // the key may have been deleted since
+            if let Some(s) = self.da.tail_node(t) {
+                self.da.set_max_data(s, data_of(t));
+            }
+        }
+        max_data
+    }
+}
+
+/// A subtree or a single entry in the top-k search.
+///
+/// Candidates are ordered by the score of the largest data they hold, then
+/// by key in reverse, so the heap pops the best score and, among equals,
+/// the smallest key.
+struct Candidate<S> {
+    score: S,
+    key: Vec<TrieChar>,
+    node: TrieIndex,
+    /// Whether this stands for the key of separate node `node` rather than
+    /// its subtree.
+    is_entry: bool,
+}
+
+impl<S: Ord> PartialEq for Candidate<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: Ord> Eq for Candidate<S> {}
+
+impl<S: Ord> PartialOrd for Candidate<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Ord> Ord for Candidate<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .cmp(&other.score)
+            .then_with(|| Reverse(&self.key).cmp(&Reverse(&other.key)))
+            .then_with(|| self.is_entry.cmp(&other.is_entry))
+    }
+}
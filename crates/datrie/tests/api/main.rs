@@ -1,11 +1,36 @@
+mod test_aho_corasick;
+mod test_automaton;
 mod test_byte_alpha;
 mod test_byte_list;
+mod test_byte_trie;
+mod test_collation;
+mod test_count_prefix;
+mod test_diff;
+mod test_entry;
+mod test_entry_id;
 mod test_file;
+mod test_fuzzy;
+mod test_iter;
 mod test_iterator;
+mod test_lattice;
+mod test_map_api;
+mod test_multimap;
 mod test_nonalpha;
 mod test_null_trie;
+mod test_pattern;
+mod test_range;
+mod test_rank;
+mod test_remap;
+mod test_remove_prefix;
+mod test_saved_state;
+mod test_segment;
 mod test_serialization;
+mod test_set;
+mod test_set_ops;
 mod test_store_retrieve;
+mod test_successor;
+mod test_suffix_index;
 mod test_term_state;
+mod test_top_k;
 mod test_walk;
 mod utils;
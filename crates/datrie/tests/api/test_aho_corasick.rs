@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use datrie::{AhoCorasick, AlphaChar, AlphaStr, DatrieResult, MatchKind, TextMatch};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, text_of};
+
+fn matcher(words: &BTreeMap<String, i32>) -> DatrieResult<AhoCorasick> {
+    let mut test_trie = en_trie_new()?;
+    for (word, &data) in words {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data));
+    }
+    Ok(AhoCorasick::new(&test_trie))
+}
+
+fn brute_overlapping(words: &BTreeMap<String, i32>, text: &str) -> Vec<TextMatch> {
+    let mut found = Vec::new();
+    for start in 0..text.len() {
+        for end in start + 1..=text.len() {
+            if let Some(&data) = words.get(&text[start..end]) {
+                found.push(TextMatch { start, end, data });
+            }
+        }
+    }
+    found.sort_by_key(|m| (m.end, m.start));
+    found
+}
+
+fn brute_leftmost_longest(words: &BTreeMap<String, i32>, text: &str) -> Vec<TextMatch> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let longest = (start + 1..=text.len()).rev().find_map(|end| {
+            words
+                .get(&text[start..end])
+                .map(|&data| TextMatch { start, end, data })
+        });
+        match longest {
+            Some(m) => {
+                start = m.end;
+                found.push(m);
+            }
+            None => start += 1,
+        }
+    }
+    found
+}
+
+#[test]
+fn test_aho_corasick_classic() -> DatrieResult<()> {
+    msg_step("Building matcher for he/she/his/hers");
+    let words: BTreeMap<String, i32> = [("he", 1), ("she", 2), ("his", 3), ("hers", 4)]
+        .iter()
+        .map(|&(w, d)| (w.to_string(), d))
+        .collect();
+    let ac = matcher(&words)?;
+
+    msg_step("Finding overlapping matches");
+    let text = text_of("ushers");
+    let m = |start, end, data| TextMatch { start, end, data };
+    assert_eq!(
+        ac.find_overlapping(&text),
+        [m(1, 4, 2), m(2, 4, 1), m(2, 6, 4)]
+    );
+
+    msg_step("Finding leftmost-longest matches");
+    assert_eq!(ac.find_leftmost_longest(&text), [m(1, 4, 2)]);
+    assert_eq!(
+        ac.find_leftmost_longest(&text_of("hershis")),
+        [m(0, 4, 4), m(4, 7, 3)]
+    );
+
+    msg_step("Scanning a stream with characters outside the alpha map");
+    let stream = "he.SHE she!".chars().map(|c| c as AlphaChar);
+    let found: Vec<TextMatch> = ac.find_iter(stream, MatchKind::Overlapping).collect();
+    assert_eq!(found, [m(0, 2, 1), m(7, 10, 2), m(8, 10, 1)]);
+    Ok(())
+}
+
+#[test]
+fn test_aho_corasick_against_brute_force() -> DatrieResult<()> {
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        msg_step("Building matcher for random keys");
+        let mut words = BTreeMap::new();
+        for data in 0..rng.gen_range(1..30) {
+            let len = rng.gen_range(1..6);
+            let word: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+            words.insert(word, data);
+        }
+        let ac = matcher(&words)?;
+
+        msg_step("Comparing matches with brute force");
+        for _ in 0..20 {
+            let len = rng.gen_range(0..40);
+            let text: String = (0..len).map(|_| rng.gen_range('a'..='d')).collect();
+            let mut found = ac.find_overlapping(&text_of(&text));
+            found.sort_by_key(|m| (m.end, m.start));
+            assert_eq!(found, brute_overlapping(&words, &text), "text '{}'", text);
+            assert_eq!(
+                ac.find_leftmost_longest(&text_of(&text)),
+                brute_leftmost_longest(&words, &text),
+                "text '{}'",
+                text
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+use datrie::{AlphaChar, AlphaStr, Automaton, DatrieResult, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+/// Accepts keys that contain `needle` and are at most `max_len` chars long.
+struct Contains {
+    needle: Vec<AlphaChar>,
+    max_len: usize,
+    /// Number of `accept` calls, to check that dead branches are pruned.
+    steps: Cell<usize>,
+}
+
+impl Contains {
+    fn new(needle: &str, max_len: usize) -> Contains {
+        Contains {
+            needle: needle.chars().map(|c| c as AlphaChar).collect(),
+            max_len,
+            steps: Cell::new(0),
+        }
+    }
+}
+
+impl Automaton for Contains {
+    /// Key length, and length of the longest needle prefix ending the key.
+    type State = (usize, usize);
+
+    fn start(&self) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn accept(&self, &(len, matched): &(usize, usize), ac: AlphaChar) -> (usize, usize) {
+        self.steps.set(self.steps.get() + 1);
+        if matched == self.needle.len() {
+            return (len + 1, matched);
+        }
+        // the needles used here have no repeated chars, so a mismatch
+        // restarts the match from the current char
+        let matched = if self.needle[matched] == ac {
+            matched + 1
+        } else {
+            usize::from(self.needle[0] == ac)
+        };
+        (len + 1, matched)
+    }
+
+    fn is_match(&self, &(_, matched): &(usize, usize)) -> bool {
+        matched == self.needle.len()
+    }
+
+    fn can_match(&self, &(len, _): &(usize, usize)) -> bool {
+        len <= self.max_len
+    }
+}
+
+fn random_trie(rng: &mut impl Rng) -> DatrieResult<(Trie, BTreeMap<String, i32>)> {
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for data in 0..500 {
+        let len = rng.gen_range(0..9);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='d')).collect();
+        let key = alpha_key(&word);
+        test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+        model.insert(word, data);
+    }
+    Ok((test_trie, model))
+}
+
+#[test]
+fn test_search_against_model() -> DatrieResult<()> {
+    msg_step("Preparing random trie");
+    let mut rng = rand::thread_rng();
+    let (test_trie, model) = random_trie(&mut rng)?;
+
+    msg_step("Searching with a substring automaton");
+    for (needle, max_len) in [("ab", 8), ("cd", 4), ("bad", 6), ("", 2)] {
+        let aut = Contains::new(needle, max_len);
+        let found: Vec<(String, i32)> = test_trie
+            .search(&aut)
+            .map(|(key, data)| (to_string(&key), data))
+            .collect();
+        let expected: Vec<(String, i32)> = model
+            .iter()
+            .filter(|(word, _)| word.contains(needle) && word.len() <= max_len)
+            .map(|(word, &data)| (word.clone(), data))
+            .collect();
+        assert_eq!(found, expected, "needle '{}', max_len {}", needle, max_len);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_search_prunes_dead_branches() -> DatrieResult<()> {
+    msg_step("Preparing random trie");
+    let mut rng = rand::thread_rng();
+    let (test_trie, model) = random_trie(&mut rng)?;
+
+    msg_step("Counting automaton steps");
+    let unbounded = Contains::new("a", usize::MAX);
+    let all = test_trie.search(&unbounded).count();
+    let short = Contains::new("a", 2);
+    let few = test_trie.search(&short).count();
+    assert_eq!(all, model.keys().filter(|word| word.contains('a')).count());
+    assert!(few <= all);
+    // only keys of up to 2 chars, and one more char per branch, are walked
+    assert!(short.steps.get() < unbounded.steps.get());
+    assert!(short.steps.get() <= 4 + 4 * 4 + 4 * 4 * 4);
+
+    msg_step("Stopping a search early");
+    let first = test_trie.search(&unbounded).next();
+    let expected = model.iter().find(|(word, _)| word.contains('a'));
+    assert_eq!(
+        first.map(|(key, data)| (to_string(&key), data)),
+        expected.map(|(word, &data)| (word.clone(), data))
+    );
+    Ok(())
+}
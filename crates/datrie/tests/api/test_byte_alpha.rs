@@ -23,6 +23,8 @@
 //  *          Based on test case in issue #6
 //  *          https://github.com/tlwg/libdatrie/issues/6
 //  */
+use std::io::Cursor;
+
 use datrie::{
     AlphaMap, AlphaStr, DatrieResult, {Trie, TrieData, DA_TRUE},
 };
@@ -45,7 +47,8 @@ fn test_byte_alpha() -> DatrieResult<()> {
         let mut test_trie = Trie::new(&alpha_map)?;
 
         msg_step("Storing key to test trie");
-        let key = AlphaStr::from_slice_with_nul(&[0xff, 0xff, 0]).unwrap();
+        // char 0 takes trie char 1, so 0xfe is the last byte with a code
+        let key = AlphaStr::from_slice_with_nul(&[0xfe, 0xfe, 0]).unwrap();
         assert!(
             Trie::store(&mut test_trie, key, TEST_DATA),
             "Fail to store key to test trie\n"
@@ -57,6 +60,34 @@ fn test_byte_alpha() -> DatrieResult<()> {
             Some(TEST_DATA),
             "Fail to retrieve key from test trie\n"
         );
+
+        msg_step("Rejecting a key past the last trie char");
+        let key = AlphaStr::from_slice_with_nul(&[0xff, 0xff, 0]).unwrap();
+        assert!(
+            !Trie::store(&mut test_trie, key, TEST_DATA),
+            "Key past the last trie char is stored\n"
+        );
+        assert_eq!(Trie::retrieve(&test_trie, key), None);
+        let empty = AlphaStr::from_slice_with_nul(&[0]).unwrap();
+        assert_eq!(Trie::retrieve(&test_trie, empty), None);
     }
     Ok(())
 }
+
+#[test]
+fn test_byte_alpha_baseline_file() -> DatrieResult<()> {
+    msg_step("Reading a file with a map from 0x00, as written by libdatrie");
+    // range 0x00..0xfe, holding "AB" with data 7
+    let file = include_bytes!("data/byte_range_00_fe.tri");
+    let mut test_trie = Trie::fread_safe(&mut Cursor::new(&file[..]))?;
+    let key = AlphaStr::from_slice_with_nul(&[0x41, 0x42, 0]).unwrap();
+    assert_eq!(test_trie.retrieve(key), Some(7));
+    let keys: Vec<_> = test_trie.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec![vec![0x41, 0x42]]);
+
+    msg_step("Writing it back unchanged");
+    let mut buf = Vec::new();
+    test_trie.serialize_safe(&mut buf)?;
+    assert_eq!(buf, file);
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use datrie::{ByteTrie, DatrieResult, ErrorKind};
+use rand::Rng;
+
+use crate::utils::{en_trie_new, msg_step};
+
+const KEYS: &[&[u8]] = &[
+    b"",
+    b"\x00",
+    b"\x00\x00",
+    b"\x01",
+    b"\x01\x01",
+    b"\x01\x02",
+    b"\x02",
+    b"\xff",
+    b"\xff\x00\xff",
+    b"hash",
+    b"hash\x00",
+];
+
+#[test]
+fn test_byte_trie_escaped_keys() -> DatrieResult<()> {
+    msg_step("Storing keys with NUL and escape bytes");
+    let mut test_trie = ByteTrie::new()?;
+    for (data, key) in KEYS.iter().enumerate() {
+        assert_eq!(
+            test_trie.insert(key, data as i32),
+            None,
+            "Failed to insert key {:?}",
+            key
+        );
+    }
+
+    msg_step("Retrieving keys");
+    for (data, key) in KEYS.iter().enumerate() {
+        assert_eq!(
+            test_trie.get(key),
+            Some(data as i32),
+            "Failed to get key {:?}",
+            key
+        );
+    }
+    assert_eq!(test_trie.get(b"\x00\x00\x00"), None);
+    assert_eq!(test_trie.get(b"has"), None);
+
+    msg_step("Removing keys");
+    assert_eq!(test_trie.remove(b"\x00"), Some(1));
+    assert_eq!(test_trie.remove(b"\x00"), None);
+    assert_eq!(test_trie.get(b"\x00"), None);
+    assert_eq!(test_trie.get(b"\x00\x00"), Some(2));
+    assert_eq!(test_trie.get(b"\x01\x01"), Some(4));
+    Ok(())
+}
+
+#[test]
+fn test_byte_trie_random_keys() -> DatrieResult<()> {
+    msg_step("Storing random keys");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = ByteTrie::new()?;
+    let mut model = HashMap::new();
+    for data in 0..500 {
+        let len = rng.gen_range(0..12);
+        let key: Vec<u8> = (0..len).map(|_| rng.gen_range(0..4) * 0x55).collect();
+        if model.contains_key(&key) {
+            continue;
+        }
+        assert_eq!(test_trie.insert(&key, data), None);
+        model.insert(key, data);
+    }
+
+    msg_step("Removing every other key");
+    let mut removed = Vec::new();
+    for (i, key) in model.keys().enumerate() {
+        if i % 2 == 0 {
+            assert_eq!(test_trie.remove(key), model.get(key).copied());
+            removed.push(key.clone());
+        }
+    }
+    for key in &removed {
+        model.remove(key);
+    }
+
+    msg_step("Checking against model");
+    for (key, data) in &model {
+        assert_eq!(test_trie.get(key), Some(*data), "key {:?}", key);
+    }
+    for key in &removed {
+        assert_eq!(test_trie.get(key), None, "key {:?}", key);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_byte_trie_serialization() -> DatrieResult<()> {
+    msg_step("Saving byte trie");
+    let mut test_trie = ByteTrie::new()?;
+    for (data, key) in KEYS.iter().enumerate() {
+        assert_eq!(test_trie.insert(key, data as i32), None);
+    }
+    let mut buf = Vec::new();
+    test_trie.serialize_safe(&mut buf)?;
+    assert_eq!(buf.len(), test_trie.get_serialized_size());
+    assert!(!test_trie.is_dirty());
+
+    msg_step("Loading byte trie");
+    let loaded_trie = ByteTrie::fread_safe(&mut Cursor::new(buf))?;
+    for (data, key) in KEYS.iter().enumerate() {
+        assert_eq!(loaded_trie.get(key), Some(data as i32));
+    }
+
+    msg_step("Loading a trie with another alpha map");
+    let mut en_trie = en_trie_new()?;
+    let mut buf = Vec::new();
+    en_trie.serialize_safe(&mut buf)?;
+    let err = ByteTrie::fread_safe(&mut Cursor::new(buf)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    Ok(())
+}
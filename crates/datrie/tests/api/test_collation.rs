@@ -0,0 +1,57 @@
+use std::io::Cursor;
+
+use datrie::{AlphaChar, AlphaMap, AlphaStr, DatrieResult, Trie};
+
+use crate::utils::{alpha_key, msg_step, text_of, to_string, trie_entries};
+
+fn keys_of(trie: &Trie) -> Vec<String> {
+    trie_entries(trie)
+        .into_iter()
+        .map(|(key, _)| to_string(&key))
+        .collect()
+}
+
+#[test]
+fn test_collation_order() -> DatrieResult<()> {
+    msg_step("Preparing alpha map with custom symbol order");
+    let order = text_of("bcad");
+    let alpha_map = AlphaMap::from_ordered_symbols(&order)?;
+
+    msg_step("Preparing trie");
+    let mut test_trie = Trie::new(&alpha_map)?;
+    for (data, word) in ["a", "ab", "b", "ba", "bad", "c", "dab"].iter().enumerate() {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+
+    msg_step("Iterating in collation order");
+    let expected = ["b", "ba", "bad", "c", "a", "ab", "dab"];
+    assert_eq!(keys_of(&test_trie), expected);
+
+    msg_step("Reloading serialized trie");
+    let mut buf = Vec::new();
+    test_trie.serialize_safe(&mut buf)?;
+    assert_eq!(buf.len(), test_trie.get_serialized_size());
+    let loaded_trie = Trie::fread_safe(&mut Cursor::new(buf))?;
+    assert_eq!(loaded_trie.alpha_map.symbol_order(), order.as_slice());
+    assert_eq!(keys_of(&loaded_trie), expected);
+    Ok(())
+}
+
+#[test]
+fn test_collation_by_comparator() -> DatrieResult<()> {
+    msg_step("Preparing alpha map ordered by a comparator");
+    let mut alpha_map = AlphaMap::default();
+    alpha_map.add_range('a' as AlphaChar, 'z' as AlphaChar)?;
+    // vowels sort before consonants
+    let is_vowel = |c: &AlphaChar| "aeiou".contains(char::from_u32(*c).unwrap());
+    alpha_map.sort_symbols_by(|a, b| is_vowel(b).cmp(&is_vowel(a)).then(a.cmp(b)))?;
+
+    let mut test_trie = Trie::new(&alpha_map)?;
+    for word in ["cat", "eel", "apple", "zoo", "ice"] {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), 1));
+    }
+    assert_eq!(keys_of(&test_trie), ["apple", "eel", "ice", "cat", "zoo"]);
+    Ok(())
+}
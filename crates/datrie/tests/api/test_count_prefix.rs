@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use datrie::{AlphaStr, DatrieResult, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, get_dict_src, msg_step};
+
+fn count_prefix(trie: &Trie, prefix: &str) -> usize {
+    let key = alpha_key(prefix);
+    trie.count_prefix(AlphaStr::from_slice_with_nul(&key).unwrap())
+}
+
+fn check_counts(trie: &Trie, model: &BTreeSet<String>, prefixes: &[String]) {
+    assert_eq!(trie.len(), model.len());
+    for prefix in prefixes {
+        let expected = model
+            .iter()
+            .filter(|w| w.starts_with(prefix.as_str()))
+            .count();
+        assert_eq!(count_prefix(trie, prefix), expected, "prefix '{}'", prefix);
+    }
+}
+
+#[test]
+fn test_count_prefix_dict() -> DatrieResult<()> {
+    msg_step("Preparing trie with key counting");
+    let mut test_trie = en_trie_new()?;
+    test_trie.set_key_counting(true);
+    assert!(test_trie.is_key_counting());
+    for (data, dict_p) in get_dict_src().iter().enumerate() {
+        assert!(test_trie.store(dict_p.key, data as i32));
+    }
+    assert_eq!(test_trie.len(), get_dict_src().len());
+    assert_eq!(count_prefix(&test_trie, ""), get_dict_src().len());
+
+    msg_step("Comparing with counting off");
+    let prefixes = ["a", "ab", "p", "pr", "pro", "x", "zz"];
+    let with_counts: Vec<usize> = prefixes
+        .iter()
+        .map(|p| count_prefix(&test_trie, p))
+        .collect();
+    test_trie.set_key_counting(false);
+    assert!(!test_trie.is_key_counting());
+    let without_counts: Vec<usize> = prefixes
+        .iter()
+        .map(|p| count_prefix(&test_trie, p))
+        .collect();
+    assert_eq!(with_counts, without_counts);
+    Ok(())
+}
+
+#[test]
+fn test_count_prefix_random() -> DatrieResult<()> {
+    let mut rng = rand::thread_rng();
+    let mut prefixes = vec![String::new()];
+    for a in 'a'..='f' {
+        prefixes.push(a.to_string());
+        for b in 'a'..='f' {
+            prefixes.push(format!("{}{}", a, b));
+            prefixes.push(format!("{}{}a", a, b));
+        }
+    }
+
+    msg_step("Running random inserts and removes with key counting");
+    let mut test_trie = en_trie_new()?;
+    test_trie.set_key_counting(true);
+    let mut model = BTreeSet::new();
+    for round in 0..3000 {
+        let len = rng.gen_range(0..8);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='f')).collect();
+        let key = alpha_key(&word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        if rng.gen_bool(0.6) {
+            test_trie.insert(key, round);
+            model.insert(word);
+        } else {
+            test_trie.remove(key);
+            model.remove(&word);
+        }
+        if round % 300 == 0 {
+            check_counts(&test_trie, &model, &prefixes);
+        }
+        if round == 1500 {
+            msg_step("Restarting key counting midway");
+            test_trie.set_key_counting(false);
+            check_counts(&test_trie, &model, &prefixes);
+            test_trie.set_key_counting(true);
+        }
+    }
+    check_counts(&test_trie, &model, &prefixes);
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaChar, AlphaMap, AlphaStr, DatrieResult, DiffEntry, ErrorKind, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, text_of};
+
+fn model_trie(model: &BTreeMap<String, i32>) -> DatrieResult<Trie> {
+    let mut trie = en_trie_new()?;
+    for (word, &data) in model {
+        let key = alpha_key(word);
+        assert!(trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data));
+    }
+    Ok(trie)
+}
+
+#[test]
+fn test_diff() -> DatrieResult<()> {
+    msg_step("Preparing old and new tries");
+    let old: BTreeMap<String, i32> = [("ant", 1), ("bee", 2), ("cat", 3), ("cow", 4)]
+        .iter()
+        .map(|&(word, data)| (word.to_string(), data))
+        .collect();
+    let new: BTreeMap<String, i32> = [("ant", 1), ("bee", 5), ("cattle", 6), ("cow", 4)]
+        .iter()
+        .map(|&(word, data)| (word.to_string(), data))
+        .collect();
+    let old_trie = model_trie(&old)?;
+    let new_trie = model_trie(&new)?;
+
+    msg_step("Diffing");
+    let diff: Vec<DiffEntry> = old_trie.diff(&new_trie)?.collect();
+    assert_eq!(
+        diff,
+        [
+            DiffEntry::Changed(text_of("bee"), 2, 5),
+            DiffEntry::Removed(text_of("cat"), 3),
+            DiffEntry::Added(text_of("cattle"), 6),
+        ]
+    );
+    assert_eq!(old_trie.diff(&old_trie)?.count(), 0);
+
+    msg_step("Diffing tries with different alpha maps");
+    let mut other_map = AlphaMap::default();
+    other_map.add_range('a' as AlphaChar, 'm' as AlphaChar)?;
+    let other_trie = Trie::new(&other_map)?;
+    assert_eq!(
+        old_trie.diff(&other_trie).err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_diff_against_btree_map() -> DatrieResult<()> {
+    let mut rng = rand::thread_rng();
+    let mut random_model = || -> BTreeMap<String, i32> {
+        (0..60)
+            .map(|_| {
+                let len = rng.gen_range(0..5);
+                let word: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+                (word, rng.gen_range(0..3))
+            })
+            .collect()
+    };
+    for _ in 0..20 {
+        msg_step("Preparing random tries");
+        let old = random_model();
+        let new = random_model();
+        let old_trie = model_trie(&old)?;
+        let new_trie = model_trie(&new)?;
+
+        msg_step("Comparing diffs");
+        let mut words: Vec<&String> = old.keys().chain(new.keys()).collect();
+        words.sort();
+        words.dedup();
+        let expected: Vec<DiffEntry> = words
+            .into_iter()
+            .filter_map(|word| match (old.get(word), new.get(word)) {
+                (Some(&o), None) => Some(DiffEntry::Removed(text_of(word), o)),
+                (None, Some(&n)) => Some(DiffEntry::Added(text_of(word), n)),
+                (Some(&o), Some(&n)) if o != n => Some(DiffEntry::Changed(text_of(word), o, n)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(old_trie.diff(&new_trie)?.collect::<Vec<_>>(), expected);
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use datrie::{AlphaStr, DatrieResult, Entry, ErrorKind};
+
+use crate::utils::{alpha_key, en_trie_new, msg_step};
+
+const TEXT: &str = "the quick brown fox jumps over the lazy dog the fox";
+
+#[test]
+fn test_entry_word_count() -> DatrieResult<()> {
+    msg_step("Counting words through the entry API");
+    let mut test_trie = en_trie_new()?;
+    let mut model: HashMap<&str, i32> = HashMap::new();
+    for word in TEXT.split(' ') {
+        let key = alpha_key(word);
+        *test_trie
+            .entry(AlphaStr::from_slice_with_nul(&key).unwrap())?
+            .or_insert(0) += 1;
+        *model.entry(word).or_insert(0) += 1;
+    }
+
+    msg_step("Checking counts");
+    for (word, count) in &model {
+        let key = alpha_key(word);
+        assert_eq!(
+            test_trie.get(AlphaStr::from_slice_with_nul(&key).unwrap()),
+            Some(*count),
+            "wrong count for '{}'",
+            word
+        );
+    }
+    assert_eq!(test_trie.len(), model.len());
+    Ok(())
+}
+
+#[test]
+fn test_entry_modify() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    let foo = alpha_key("foo");
+    let foo = AlphaStr::from_slice_with_nul(&foo).unwrap();
+    let food = alpha_key("food");
+    let food = AlphaStr::from_slice_with_nul(&food).unwrap();
+    assert_eq!(test_trie.insert(foo, 10), None);
+
+    msg_step("Modifying occupied and vacant entries");
+    assert_eq!(
+        *test_trie.entry(foo)?.and_modify(|d| *d *= 2).or_insert(0),
+        20
+    );
+    assert_eq!(
+        *test_trie
+            .entry(food)?
+            .and_modify(|d| *d *= 2)
+            .or_insert_with(|| 7),
+        7
+    );
+    assert_eq!(test_trie.get(foo), Some(20));
+    assert_eq!(test_trie.get(food), Some(7));
+
+    msg_step("Updating through get_mut");
+    *test_trie.get_mut(food).unwrap() += 1;
+    assert_eq!(test_trie.get(food), Some(8));
+    let fo = alpha_key("fo");
+    assert!(test_trie
+        .get_mut(AlphaStr::from_slice_with_nul(&fo).unwrap())
+        .is_none());
+
+    msg_step("Removing through an occupied entry");
+    match test_trie.entry(foo)? {
+        Entry::Occupied(entry) => assert_eq!(entry.remove(), 20),
+        Entry::Vacant(_) => panic!("'foo' should be occupied"),
+    }
+    assert_eq!(test_trie.get(foo), None);
+    assert_eq!(test_trie.get(food), Some(8));
+
+    msg_step("Inserting through a vacant entry with try_insert");
+    match test_trie.entry(foo)? {
+        Entry::Occupied(_) => panic!("'foo' should be vacant"),
+        Entry::Vacant(entry) => assert_eq!(*entry.try_insert(30)?, 30),
+    }
+    assert_eq!(test_trie.get(foo), Some(30));
+
+    msg_step("Getting the entry of an unmappable key");
+    let bad = alpha_key("Foo");
+    let err = test_trie
+        .entry(AlphaStr::from_slice_with_nul(&bad).unwrap())
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    Ok(())
+}
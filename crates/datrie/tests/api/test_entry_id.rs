@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use datrie::{AlphaStr, DatrieResult, EntryId, ErrorKind, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, text_of};
+
+#[test]
+fn test_entry_id() -> DatrieResult<()> {
+    msg_step("Inserting keys with ids");
+    let mut test_trie = en_trie_new()?;
+    let key = alpha_key("ant");
+    let ant = AlphaStr::from_slice_with_nul(&key).unwrap();
+    let id = test_trie.insert_with_id(ant, 1)?;
+    assert_eq!(test_trie.lookup_id(ant), Some(id));
+    assert_eq!(test_trie.key_of(id), Some(text_of("ant")));
+
+    msg_step("Keeping ids across branching");
+    let key = alpha_key("anthem");
+    let anthem = AlphaStr::from_slice_with_nul(&key).unwrap();
+    let anthem_id = test_trie.insert_with_id(anthem, 2)?;
+    assert_ne!(anthem_id, id);
+    assert_eq!(test_trie.insert_with_id(ant, 3)?, id);
+    assert_eq!(test_trie.get(ant), Some(3));
+    assert_eq!(test_trie.key_of(id), Some(text_of("ant")));
+    assert_eq!(test_trie.key_of(anthem_id), Some(text_of("anthem")));
+    assert_eq!(EntryId::from_index(id.index()), id);
+
+    msg_step("Looking up missing keys and ids");
+    let key = alpha_key("an");
+    assert_eq!(
+        test_trie.lookup_id(AlphaStr::from_slice_with_nul(&key).unwrap()),
+        None
+    );
+    let key = alpha_key("Ant");
+    let err = test_trie
+        .insert_with_id(AlphaStr::from_slice_with_nul(&key).unwrap(), 1)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(test_trie.delete(anthem));
+    assert_eq!(test_trie.key_of(anthem_id), None);
+    assert_eq!(test_trie.key_of(EntryId::from_index(0)), None);
+    assert_eq!(test_trie.key_of(EntryId::from_index(1000)), None);
+    Ok(())
+}
+
+#[test]
+fn test_entry_id_stability() -> DatrieResult<()> {
+    msg_step("Preparing random trie");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut ids = BTreeMap::new();
+    for data in 0..400 {
+        let len = rng.gen_range(0..8);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='z')).collect();
+        let key = alpha_key(&word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        if rng.gen_bool(0.8) {
+            let id = test_trie.insert_with_id(key, data)?;
+            if let Some(&old_id) = ids.get(&word) {
+                assert_eq!(id, old_id);
+            }
+            assert_eq!(test_trie.key_of(id), Some(text_of(&word)));
+            ids.insert(word, id);
+        } else {
+            assert_eq!(test_trie.delete(key), ids.remove(&word).is_some());
+        }
+        // ids resolve while keys move, between the changes
+        if data % 50 == 0 {
+            for (word, &id) in &ids {
+                assert_eq!(test_trie.key_of(id), Some(text_of(word)));
+            }
+        }
+    }
+
+    msg_step("Resolving ids after changes");
+    for (word, &id) in &ids {
+        let key = alpha_key(word);
+        assert_eq!(
+            test_trie.lookup_id(AlphaStr::from_slice_with_nul(&key).unwrap()),
+            Some(id)
+        );
+        assert_eq!(test_trie.key_of(id), Some(text_of(word)));
+    }
+
+    msg_step("Resolving ids after reloading");
+    let mut buf = Vec::new();
+    test_trie.serialize_safe(&mut buf)?;
+    let loaded = Trie::fread_safe(&mut Cursor::new(buf))?;
+    for (word, &id) in &ids {
+        assert_eq!(loaded.key_of(id), Some(text_of(word)));
+    }
+    Ok(())
+}
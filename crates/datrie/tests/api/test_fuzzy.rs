@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaStr, DatrieResult, EditDistance, FuzzyMatch, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+/// Plain dynamic-programming edit distance, with optional adjacent
+/// transpositions (optimal string alignment).
+fn edit_distance(a: &[char], b: &[char], transpositions: bool) -> u32 {
+    let mut d = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i as u32;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j as u32;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = u32::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j - 1] + cost)
+                .min(d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1);
+            if transpositions && i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+fn search(trie: &Trie, query: &str, max_edits: u32, metric: EditDistance) -> Vec<FuzzyMatch> {
+    let key = alpha_key(query);
+    trie.fuzzy_search_by(
+        AlphaStr::from_slice_with_nul(&key).unwrap(),
+        max_edits,
+        metric,
+    )
+}
+
+#[test]
+fn test_fuzzy_search_spelling() -> DatrieResult<()> {
+    msg_step("Preparing dictionary");
+    let mut test_trie = en_trie_new()?;
+    let words = ["hello", "help", "hell", "held", "yellow", "shell", "hallo"];
+    for (data, word) in words.iter().enumerate() {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+
+    msg_step("Searching within one edit");
+    let found: Vec<(String, u32, i32)> = search(&test_trie, "helo", 1, EditDistance::Levenshtein)
+        .into_iter()
+        .map(|m| (to_string(&m.key), m.distance, m.data))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("held".to_string(), 1, 3),
+            ("hell".to_string(), 1, 2),
+            ("hello".to_string(), 1, 0),
+            ("help".to_string(), 1, 1),
+        ]
+    );
+
+    msg_step("Searching with transpositions");
+    let key = alpha_key("hlelo");
+    let query = AlphaStr::from_slice_with_nul(&key).unwrap();
+    assert!(test_trie.fuzzy_search(query, 1).is_empty());
+    let found = search(&test_trie, "hlelo", 1, EditDistance::Damerau);
+    assert_eq!(found.len(), 1);
+    assert_eq!(to_string(&found[0].key), "hello");
+    Ok(())
+}
+
+#[test]
+fn test_fuzzy_search_against_brute_force() -> DatrieResult<()> {
+    msg_step("Preparing random dictionary");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for data in 0..300 {
+        let len = rng.gen_range(0..7);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='d')).collect();
+        let key = alpha_key(&word);
+        test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+        model.insert(word, data);
+    }
+
+    msg_step("Comparing fuzzy search with brute force");
+    for _ in 0..50 {
+        let len = rng.gen_range(0..7);
+        let query: String = (0..len).map(|_| rng.gen_range('a'..='e')).collect();
+        let query_chars: Vec<char> = query.chars().collect();
+        for max_edits in 0..3 {
+            for (metric, transpositions) in [
+                (EditDistance::Levenshtein, false),
+                (EditDistance::Damerau, true),
+            ] {
+                let expected: Vec<(String, u32, i32)> = model
+                    .iter()
+                    .filter_map(|(word, &data)| {
+                        let word_chars: Vec<char> = word.chars().collect();
+                        let d = edit_distance(&word_chars, &query_chars, transpositions);
+                        (d <= max_edits).then(|| (word.clone(), d, data))
+                    })
+                    .collect();
+                let found: Vec<(String, u32, i32)> = search(&test_trie, &query, max_edits, metric)
+                    .into_iter()
+                    .map(|m| (to_string(&m.key), m.distance, m.data))
+                    .collect();
+                assert_eq!(
+                    found, expected,
+                    "query '{}', {:?} <= {}",
+                    query, metric, max_edits
+                );
+            }
+        }
+    }
+    Ok(())
+}
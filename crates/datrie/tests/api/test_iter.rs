@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaStr, DatrieResult, ErrorKind, ResumeToken};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+#[test]
+fn test_iter_seek_and_reverse() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    let words = ["apple", "apricot", "banana", "band", "bandana", "cherry"];
+    for (data, word) in words.iter().enumerate() {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+
+    msg_step("Iterating forward and backward");
+    let forward: Vec<String> = test_trie.iter().map(|(k, _)| to_string(&k)).collect();
+    assert_eq!(forward, words);
+    let mut backward: Vec<String> = test_trie.iter().rev().map(|(k, _)| to_string(&k)).collect();
+    backward.reverse();
+    assert_eq!(backward, words);
+
+    msg_step("Seeking");
+    let mut iter = test_trie.iter();
+    let key = alpha_key("band");
+    iter.seek(AlphaStr::from_slice_with_nul(&key).unwrap())?;
+    assert_eq!(
+        iter.next().map(|(k, d)| (to_string(&k), d)),
+        Some(("band".into(), 3))
+    );
+    let key = alpha_key("bb");
+    iter.seek(AlphaStr::from_slice_with_nul(&key).unwrap())?;
+    assert_eq!(
+        iter.next_back().map(|(k, _)| to_string(&k)).as_deref(),
+        Some("cherry")
+    );
+    assert_eq!(iter.next(), None);
+    // seeking backwards brings back entries, up to the back end
+    let key = alpha_key("apple");
+    iter.seek(AlphaStr::from_slice_with_nul(&key).unwrap())?;
+    assert_eq!(iter.count(), 5);
+
+    msg_step("Seeking to a key outside the alpha map");
+    let key = alpha_key("Z");
+    let mut iter = test_trie.iter();
+    assert_eq!(
+        iter.seek(AlphaStr::from_slice_with_nul(&key).unwrap())
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+
+    msg_step("Iterating an empty trie");
+    let empty_trie = en_trie_new()?;
+    assert_eq!(empty_trie.iter().next(), None);
+    assert_eq!(empty_trie.iter().next_back(), None);
+    Ok(())
+}
+
+#[test]
+fn test_iter_resume_pages() -> DatrieResult<()> {
+    msg_step("Preparing random trie");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for data in 0..200 {
+        let len = rng.gen_range(0..6);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='d')).collect();
+        let key = alpha_key(&word);
+        test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+        model.insert(word, data);
+    }
+
+    msg_step("Listing in pages through serialized tokens");
+    let mut listed = Vec::new();
+    let mut token = ResumeToken::start().to_bytes();
+    loop {
+        let mut iter = test_trie.iter_from(&ResumeToken::from_bytes(&token)?)?;
+        let page: Vec<_> = iter.by_ref().take(7).collect();
+        if page.is_empty() {
+            break;
+        }
+        listed.extend(page.into_iter().map(|(k, d)| (to_string(&k), d)));
+        token = iter.resume_token().to_bytes();
+    }
+    let expected: Vec<_> = model.into_iter().collect();
+    assert_eq!(listed, expected);
+
+    msg_step("Rejecting malformed tokens");
+    for bad in [&[][..], &[2], &[0, 0, 0, 0x61], &[1, 0, 0, 0, 0]] {
+        assert_eq!(
+            ResumeToken::from_bytes(bad).err().map(|e| e.kind()),
+            Some(ErrorKind::InvalidArgument)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use datrie::segment::{Edge, Lattice};
+use datrie::{AlphaChar, AlphaStr, DatrieResult};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, text_of};
+
+/// Costs of all paths through `lattice`, by plain depth-first search.
+fn all_path_costs(lattice: &Lattice, pos: usize, cost: f64, costs: &mut Vec<f64>) {
+    if pos == lattice.text_len() {
+        costs.push(cost);
+        return;
+    }
+    for edge in lattice.edges_from(pos) {
+        all_path_costs(lattice, edge.end, cost + edge.data as f64, costs);
+    }
+}
+
+#[test]
+fn test_lattice_edges() -> DatrieResult<()> {
+    msg_step("Preparing dictionary");
+    let mut test_trie = en_trie_new()?;
+    let words = ["end", "me", "men", "mend", "the", "them", "theme"];
+    for (data, word) in words.iter().enumerate() {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+
+    msg_step("Building lattice");
+    let lattice = test_trie.build_lattice(&text_of("themend"));
+    let e = |start, end, data| Edge { start, end, data };
+    assert_eq!(lattice.text_len(), 7);
+    assert_eq!(lattice.edges().len(), 7);
+    assert_eq!(lattice.edges_from(0), [e(0, 3, 4), e(0, 4, 5), e(0, 5, 6)]);
+    assert_eq!(lattice.edges_from(3), [e(3, 5, 1), e(3, 6, 2), e(3, 7, 3)]);
+    assert!(lattice.edges_from(1).is_empty());
+    assert!(lattice.edges_from(8).is_empty());
+    let to_end: Vec<Edge> = lattice.edges_to(7).copied().collect();
+    assert_eq!(to_end, [e(3, 7, 3), e(4, 7, 0)]);
+    assert_eq!(lattice.edges_to(5).count(), 2);
+    assert_eq!(lattice.edges_to(0).count(), 0);
+
+    msg_step("Listing best paths");
+    let paths: Vec<(Vec<Edge>, f64)> = lattice
+        .best_paths(|edge| edge.data as f64)
+        .map(|path| (path.edges, path.cost))
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            (vec![e(0, 4, 5), e(4, 7, 0)], 5.0),
+            (vec![e(0, 3, 4), e(3, 7, 3)], 7.0),
+        ]
+    );
+
+    msg_step("Text with an unknown char has no path");
+    let lattice = test_trie.build_lattice(&text_of("thexend"));
+    assert_eq!(lattice.edges().len(), 2);
+    assert_eq!(lattice.best_paths(|_| 1.0).count(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_lattice_paths_against_brute_force() -> DatrieResult<()> {
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        msg_step("Preparing random dictionary");
+        let mut test_trie = en_trie_new()?;
+        for _ in 0..20 {
+            let len = rng.gen_range(1..4);
+            let word: String = (0..len).map(|_| rng.gen_range('a'..='b')).collect();
+            let key = alpha_key(&word);
+            test_trie.insert(
+                AlphaStr::from_slice_with_nul(&key).unwrap(),
+                rng.gen_range(0..10),
+            );
+        }
+
+        msg_step("Comparing path costs with brute force");
+        let len = rng.gen_range(0..12);
+        let text: Vec<AlphaChar> = (0..len)
+            .map(|_| rng.gen_range('a'..='b') as AlphaChar)
+            .collect();
+        let lattice = test_trie.build_lattice(&text);
+        let mut expected = Vec::new();
+        all_path_costs(&lattice, 0, 0.0, &mut expected);
+        expected.sort_by(f64::total_cmp);
+        let found: Vec<f64> = lattice
+            .best_paths(|edge| edge.data as f64)
+            .map(|p| p.cost)
+            .collect();
+        assert_eq!(found, expected);
+        for path in lattice.best_paths(|edge| edge.data as f64).take(5) {
+            assert_eq!(path.edges.first().map_or(0, |e| e.start), 0);
+            assert_eq!(path.edges.last().map_or(0, |e| e.end), text.len());
+            assert!(path.edges.windows(2).all(|w| w[0].end == w[1].start));
+        }
+    }
+    Ok(())
+}
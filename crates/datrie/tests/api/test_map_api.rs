@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaStr, DatrieResult, ErrorKind};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step};
+
+#[test]
+fn test_map_api_overwrite() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    let key = alpha_key("apple");
+    let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+    assert!(test_trie.is_empty());
+    assert_eq!(test_trie.insert(key, 1), None);
+    assert!(!test_trie.is_empty());
+
+    msg_step("Overwriting with store and insert");
+    assert!(test_trie.store(key, 2));
+    assert_eq!(test_trie.get(key), Some(2));
+    assert!(!test_trie.store_if_absent(key, 3));
+    assert_eq!(test_trie.get(key), Some(2));
+    assert_eq!(test_trie.insert(key, 4), Some(2));
+    assert_eq!(test_trie.get(key), Some(4));
+    assert_eq!(test_trie.len(), 1);
+
+    msg_step("Removing the only key");
+    assert_eq!(test_trie.remove(key), Some(4));
+    assert_eq!(test_trie.remove(key), None);
+    assert!(!test_trie.contains_key(key));
+    assert!(test_trie.is_empty());
+
+    msg_step("Inserting with try_insert");
+    assert_eq!(test_trie.try_insert(key, 5)?, None);
+    assert_eq!(test_trie.try_insert(key, 6)?, Some(5));
+    let bad_key = alpha_key("apple!");
+    let bad_key = AlphaStr::from_slice_with_nul(&bad_key).unwrap();
+    let err = test_trie.try_insert(bad_key, 7).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(test_trie.len(), 1);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "Trie::insert: InvalidArgument")]
+fn test_map_api_insert_unmappable_key() {
+    let mut test_trie = en_trie_new().unwrap();
+    let key = alpha_key("Apple");
+    test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), 1);
+}
+
+#[test]
+fn test_map_api_against_model() -> DatrieResult<()> {
+    msg_step("Running random operations against a BTreeMap");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for data in 0..2000 {
+        let len = rng.gen_range(1..6);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='d')).collect();
+        let key = alpha_key(&word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        match rng.gen_range(0..3) {
+            0 => assert_eq!(test_trie.insert(key, data), model.insert(word, data)),
+            1 => assert_eq!(test_trie.remove(key), model.remove(&word)),
+            _ => {
+                assert_eq!(test_trie.get(key), model.get(&word).copied());
+                assert_eq!(test_trie.contains_key(key), model.contains_key(&word));
+            }
+        }
+        assert_eq!(test_trie.len(), model.len());
+        assert_eq!(test_trie.is_empty(), model.is_empty());
+    }
+    Ok(())
+}
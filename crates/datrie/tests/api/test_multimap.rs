@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use datrie::{AlphaStr, DatrieResult, ErrorKind, Trie, TrieMultiMap};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_alpha_map_new, msg_step};
+
+#[test]
+fn test_multimap_values() -> DatrieResult<()> {
+    msg_step("Preparing multimap");
+    let mut multimap = TrieMultiMap::new(&en_alpha_map_new()?)?;
+    let saw = alpha_key("saw");
+    let saw = AlphaStr::from_slice_with_nul(&saw).unwrap();
+    let see = alpha_key("see");
+    let see = AlphaStr::from_slice_with_nul(&see).unwrap();
+
+    msg_step("Inserting values");
+    assert!(multimap.insert_value(saw, 10)?);
+    assert!(multimap.insert_value(saw, 20)?);
+    assert!(!multimap.insert_value(saw, 10)?);
+    assert!(multimap.insert_value(see, 10)?);
+    assert_eq!(multimap.values(saw), [10, 20]);
+    assert_eq!(multimap.values(see), [10]);
+    assert_eq!(multimap.len(), 2);
+
+    msg_step("Removing values");
+    assert!(multimap.remove_value(saw, 10));
+    assert!(!multimap.remove_value(saw, 10));
+    assert_eq!(multimap.values(saw), [20]);
+    assert!(multimap.remove_value(see, 10));
+    assert!(!multimap.contains_key(see));
+    assert_eq!(multimap.values(see), []);
+    assert_eq!(multimap.remove(saw), Some(vec![20]));
+    assert!(multimap.is_empty());
+
+    msg_step("Inserting with a key outside the alpha map");
+    let bad = alpha_key("Saw");
+    assert_eq!(
+        multimap
+            .insert_value(AlphaStr::from_slice_with_nul(&bad).unwrap(), 1)
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_multimap_serialization() -> DatrieResult<()> {
+    msg_step("Preparing random multimap");
+    let mut rng = rand::thread_rng();
+    let mut multimap = TrieMultiMap::new(&en_alpha_map_new()?)?;
+    let mut model: BTreeMap<String, Vec<i32>> = BTreeMap::new();
+    for _ in 0..500 {
+        let len = rng.gen_range(1..5);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+        let key = alpha_key(&word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        let value = rng.gen_range(0..4);
+        if rng.gen_bool(0.7) {
+            let values = model.entry(word.clone()).or_default();
+            let is_new = !values.contains(&value);
+            if is_new {
+                values.push(value);
+            }
+            assert_eq!(multimap.insert_value(key, value)?, is_new);
+        } else {
+            let values = model.entry(word.clone()).or_default();
+            let pos = values.iter().position(|&v| v == value);
+            if let Some(pos) = pos {
+                values.remove(pos);
+            }
+            if values.is_empty() {
+                model.remove(&word);
+            }
+            assert_eq!(multimap.remove_value(key, value), pos.is_some());
+        }
+    }
+
+    msg_step("Saving and reloading");
+    let mut buf = Vec::new();
+    multimap.serialize_safe(&mut buf)?;
+    assert!(!multimap.is_dirty());
+    assert_eq!(buf.len(), multimap.get_serialized_size());
+    let loaded = TrieMultiMap::fread_safe(&mut Cursor::new(&buf))?;
+    assert_eq!(loaded.len(), model.len());
+    for (word, values) in &model {
+        let key = alpha_key(word);
+        assert_eq!(
+            loaded.values(AlphaStr::from_slice_with_nul(&key).unwrap()),
+            &values[..]
+        );
+    }
+
+    msg_step("Modifying the reloaded multimap");
+    let mut loaded = loaded;
+    assert!(!loaded.is_dirty());
+    let (word, values) = model.iter().next().unwrap();
+    let key = alpha_key(word);
+    let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+    assert!(loaded.insert_value(key, 10)?);
+    assert!(loaded.is_dirty());
+    let mut buf2 = Vec::new();
+    loaded.serialize_safe(&mut buf2)?;
+    assert!(!loaded.is_dirty());
+    assert_eq!(buf2.len(), buf.len() + 4);
+    assert_eq!(loaded.remove(key).map(|v| v.len()), Some(values.len() + 1));
+    assert!(loaded.is_dirty());
+    assert!(loaded.insert_value(key, 11)?);
+    assert_eq!(loaded.values(key), &[11]);
+
+    msg_step("Reading as a plain trie");
+    assert_eq!(
+        Trie::fread_safe(&mut Cursor::new(&buf))
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidFileSignature)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_multimap_malformed_lists() -> DatrieResult<()> {
+    msg_step("Preparing saved multimap");
+    let mut multimap = TrieMultiMap::new(&en_alpha_map_new()?)?;
+    let key = alpha_key("ab");
+    let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+    assert!(multimap.insert_value(key, 5)?);
+    let mut buf = Vec::new();
+    multimap.serialize_safe(&mut buf)?;
+    let list = [0, 0, 0, 1, 0, 0, 0, 5];
+    let at = buf.windows(list.len()).rposition(|w| w == list).unwrap();
+    let with_list = |new: &[u8]| {
+        let mut patched = buf[..at].to_vec();
+        patched.extend_from_slice(new);
+        patched.extend_from_slice(&buf[at + list.len()..]);
+        patched
+    };
+
+    msg_step("Reading a key with no values");
+    let mut loaded = TrieMultiMap::fread_safe(&mut Cursor::new(with_list(&[0, 0, 0, 0])))?;
+    assert!(loaded.contains_key(key));
+    assert!(loaded.values(key).is_empty());
+    assert!(!loaded.insert_value(key, 6)?);
+    assert!(!loaded.remove_value(key, 5));
+    assert_eq!(loaded.remove(key), None);
+    assert!(!loaded.contains_key(key));
+
+    msg_step("Reading bad value counts");
+    let huge = with_list(&[0x7f, 0xff, 0xff, 0xff, 0, 0, 0, 5]);
+    assert_eq!(
+        TrieMultiMap::fread_safe(&mut Cursor::new(huge))
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::Bug)
+    );
+    let negative = with_list(&[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 5]);
+    assert_eq!(
+        TrieMultiMap::fread_safe(&mut Cursor::new(negative))
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::Bug)
+    );
+    Ok(())
+}
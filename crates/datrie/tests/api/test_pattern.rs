@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaStr, DatrieResult, ErrorKind, Pattern, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+fn matching_words(trie: &Trie, pattern: &str) -> DatrieResult<Vec<String>> {
+    let pattern: Pattern = pattern.parse()?;
+    Ok(trie
+        .match_pattern(&pattern)
+        .into_iter()
+        .map(|(key, _)| to_string(&key))
+        .collect())
+}
+
+/// Plain recursive glob matcher for `?` and `*`.
+fn glob_match(pattern: &[char], word: &[char]) -> bool {
+    match pattern.split_first() {
+        None => word.is_empty(),
+        Some(('*', rest)) => (0..=word.len()).any(|i| glob_match(rest, &word[i..])),
+        Some((&p, rest)) => match word.split_first() {
+            Some((&c, word_rest)) => (p == '?' || p == c) && glob_match(rest, word_rest),
+            None => false,
+        },
+    }
+}
+
+#[test]
+fn test_match_pattern_crossword() -> DatrieResult<()> {
+    msg_step("Preparing lexicon");
+    let mut test_trie = en_trie_new()?;
+    let words = [
+        "cat", "cart", "cot", "coat", "cut", "cute", "thank", "think", "thick", "the",
+    ];
+    for (data, word) in words.iter().enumerate() {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+
+    msg_step("Matching wildcards");
+    assert_eq!(
+        matching_words(&test_trie, "c?t*")?,
+        ["cat", "cot", "cut", "cute"]
+    );
+    assert_eq!(
+        matching_words(&test_trie, "th??k")?,
+        ["thank", "thick", "think"]
+    );
+    assert_eq!(matching_words(&test_trie, "*a*t")?, ["cart", "cat", "coat"]);
+    assert_eq!(matching_words(&test_trie, "the")?, ["the"]);
+    assert_eq!(matching_words(&test_trie, "th")?, Vec::<String>::new());
+
+    msg_step("Matching character classes");
+    assert_eq!(matching_words(&test_trie, "c[ao]t")?, ["cat", "cot"]);
+    assert_eq!(matching_words(&test_trie, "c[!ao]t*")?, ["cut", "cute"]);
+    assert_eq!(
+        matching_words(&test_trie, "th[a-i]?k")?,
+        ["thank", "thick", "think"]
+    );
+    assert_eq!(matching_words(&test_trie, "th[^a-h]*")?, ["thick", "think"]);
+
+    msg_step("Checking data of matches");
+    let pattern: Pattern = "cut?".parse()?;
+    let key: Vec<u32> = "cute".chars().map(|c| c as u32).collect();
+    assert_eq!(test_trie.match_pattern(&pattern), [(key, 5)]);
+
+    msg_step("Parsing bad patterns");
+    for bad in ["c[at", "ca\\", "[]"] {
+        let err = bad.parse::<Pattern>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument, "pattern '{}'", bad);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_match_pattern_against_glob() -> DatrieResult<()> {
+    msg_step("Preparing random lexicon");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for data in 0..300 {
+        let len = rng.gen_range(0..7);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+        let key = alpha_key(&word);
+        test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+        model.insert(word, data);
+    }
+
+    msg_step("Comparing with a plain glob matcher");
+    let symbols = ['a', 'b', 'c', '?', '*'];
+    for _ in 0..200 {
+        let len = rng.gen_range(0..6);
+        let pattern: String = (0..len)
+            .map(|_| symbols[rng.gen_range(0..symbols.len())])
+            .collect();
+        let pattern_chars: Vec<char> = pattern.chars().collect();
+        let expected: Vec<String> = model
+            .keys()
+            .filter(|word| glob_match(&pattern_chars, &word.chars().collect::<Vec<_>>()))
+            .cloned()
+            .collect();
+        assert_eq!(
+            matching_words(&test_trie, &pattern)?,
+            expected,
+            "pattern '{}'",
+            pattern
+        );
+    }
+    Ok(())
+}
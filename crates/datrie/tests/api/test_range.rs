@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use datrie::{AlphaChar, AlphaStr, DatrieResult, ErrorKind, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+fn random_word(rng: &mut impl Rng) -> String {
+    let len = rng.gen_range(0..6);
+    (0..len).map(|_| rng.gen_range('a'..='d')).collect()
+}
+
+fn random_bound<'k>(rng: &mut impl Rng, key: &'k [AlphaChar]) -> Bound<&'k AlphaStr> {
+    let key = AlphaStr::from_slice_with_nul(key).unwrap();
+    match rng.gen_range(0..3) {
+        0 => Bound::Included(key),
+        1 => Bound::Excluded(key),
+        _ => Bound::Unbounded,
+    }
+}
+
+fn string_bound(bound: Bound<&AlphaStr>) -> Bound<String> {
+    match bound {
+        Bound::Included(key) => Bound::Included(to_string(key.to_slice())),
+        Bound::Excluded(key) => Bound::Excluded(to_string(key.to_slice())),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+fn words(trie: &Trie, range: (Bound<&AlphaStr>, Bound<&AlphaStr>)) -> DatrieResult<Vec<String>> {
+    Ok(trie.range(range)?.map(|(key, _)| to_string(&key)).collect())
+}
+
+#[test]
+fn test_range_bounds() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    for (data, word) in ["apple", "apricot", "banana", "band", "bandana", "cherry"]
+        .iter()
+        .enumerate()
+    {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+    let b = alpha_key("b");
+    let b = AlphaStr::from_slice_with_nul(&b).unwrap();
+    let band = alpha_key("band");
+    let band = AlphaStr::from_slice_with_nul(&band).unwrap();
+    let c = alpha_key("c");
+    let c = AlphaStr::from_slice_with_nul(&c).unwrap();
+
+    msg_step("Querying ranges");
+    let collect =
+        |iter: datrie::Range| -> Vec<String> { iter.map(|(key, _)| to_string(&key)).collect() };
+    assert_eq!(
+        collect(test_trie.range(b..c)?),
+        ["banana", "band", "bandana"]
+    );
+    assert_eq!(collect(test_trie.range(b..band)?), ["banana"]);
+    assert_eq!(collect(test_trie.range(b..=band)?), ["banana", "band"]);
+    assert_eq!(
+        collect(test_trie.range(band..)?),
+        ["band", "bandana", "cherry"]
+    );
+    assert_eq!(collect(test_trie.range(..b)?), ["apple", "apricot"]);
+    assert_eq!(collect(test_trie.range(..)?).len(), 6);
+    assert_eq!(collect(test_trie.range(c..b)?), Vec::<String>::new());
+    assert_eq!(
+        test_trie.range(b..c)?.next(),
+        Some(("banana".chars().map(|c| c as AlphaChar).collect(), 2))
+    );
+
+    msg_step("Iterating from both ends");
+    let mut range = test_trie.range(b..)?;
+    assert_eq!(
+        range.next_back().map(|(k, _)| to_string(&k)).as_deref(),
+        Some("cherry")
+    );
+    assert_eq!(
+        range.next().map(|(k, _)| to_string(&k)).as_deref(),
+        Some("banana")
+    );
+    assert_eq!(collect(range), ["band", "bandana"]);
+    let reversed: Vec<String> = test_trie
+        .range(..)?
+        .rev()
+        .map(|(k, _)| to_string(&k))
+        .collect();
+    assert_eq!(reversed.first().map(String::as_str), Some("cherry"));
+    assert_eq!(reversed.last().map(String::as_str), Some("apple"));
+
+    msg_step("Querying with a bound outside the alpha map");
+    let bad = alpha_key("B");
+    let bad = AlphaStr::from_slice_with_nul(&bad).unwrap();
+    assert_eq!(
+        test_trie.range(bad..).err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_range_against_btree_map() -> DatrieResult<()> {
+    msg_step("Preparing random trie");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for data in 0..300 {
+        let word = random_word(&mut rng);
+        let key = alpha_key(&word);
+        test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+        model.insert(word, data);
+    }
+
+    msg_step("Comparing ranges");
+    for _ in 0..300 {
+        let lo = alpha_key(&random_word(&mut rng));
+        let hi = alpha_key(&random_word(&mut rng));
+        let lo = random_bound(&mut rng, &lo);
+        let hi = random_bound(&mut rng, &hi);
+        let bounds = (string_bound(lo), string_bound(hi));
+        let expected: Vec<String> = match (&bounds.0, &bounds.1) {
+            // BTreeMap panics on these
+            (Bound::Included(l) | Bound::Excluded(l), Bound::Included(h) | Bound::Excluded(h))
+                if l > h =>
+            {
+                Vec::new()
+            }
+            (Bound::Excluded(l), Bound::Excluded(h)) if l == h => Vec::new(),
+            _ => model
+                .range(bounds.clone())
+                .map(|(k, _)| k.clone())
+                .collect(),
+        };
+        assert_eq!(words(&test_trie, (lo, hi))?, expected, "range {:?}", bounds);
+        let mut reversed: Vec<String> = test_trie
+            .range((lo, hi))?
+            .rev()
+            .map(|(key, _)| to_string(&key))
+            .collect();
+        reversed.reverse();
+        assert_eq!(reversed, expected, "reversed range {:?}", bounds);
+    }
+    Ok(())
+}
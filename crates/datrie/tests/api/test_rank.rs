@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaStr, DatrieResult, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string, trie_entries};
+
+fn rank(trie: &Trie, word: &str) -> Option<usize> {
+    let key = alpha_key(word);
+    trie.rank(AlphaStr::from_slice_with_nul(&key).unwrap())
+}
+
+#[test]
+fn test_nth_and_rank() -> DatrieResult<()> {
+    for key_counting in [true, false] {
+        msg_step("Preparing random trie");
+        let mut rng = rand::thread_rng();
+        let mut test_trie = en_trie_new()?;
+        test_trie.set_key_counting(key_counting);
+        let mut model = BTreeMap::new();
+        for data in 0..400 {
+            let len = rng.gen_range(0..7);
+            let word: String = (0..len).map(|_| rng.gen_range('a'..='e')).collect();
+            let key = alpha_key(&word);
+            test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+            model.insert(word, data);
+        }
+
+        msg_step("Checking nth and rank against iteration order");
+        let entries = trie_entries(&test_trie);
+        assert_eq!(entries.len(), model.len());
+        for (n, (key, data)) in entries.iter().enumerate() {
+            assert_eq!(test_trie.nth(n).as_ref(), Some(&(key.clone(), *data)));
+            assert_eq!(rank(&test_trie, &to_string(key)), Some(n));
+        }
+        assert_eq!(test_trie.nth(entries.len()), None);
+
+        msg_step("Ranking absent keys");
+        for word in ["f", "abcdefg", "eeeeeee"] {
+            assert_eq!(rank(&test_trie, word), None);
+        }
+        for _ in 0..50 {
+            let len = rng.gen_range(0..7);
+            let word: String = (0..len).map(|_| rng.gen_range('a'..='e')).collect();
+            let expected = model
+                .contains_key(&word)
+                .then(|| model.range(..word.clone()).count());
+            assert_eq!(rank(&test_trie, &word), expected, "word '{}'", word);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_nth_empty_trie() -> DatrieResult<()> {
+    let test_trie = en_trie_new()?;
+    assert_eq!(test_trie.nth(0), None);
+    assert_eq!(rank(&test_trie, ""), None);
+    Ok(())
+}
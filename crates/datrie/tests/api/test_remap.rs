@@ -0,0 +1,52 @@
+use datrie::{AlphaMap, AlphaStr, DatrieResult, ErrorKind};
+
+use crate::utils::{alpha_key, en_trie_new, get_dict_src, msg_step};
+
+#[test]
+fn test_remap_alphabet() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    let dict_src = get_dict_src();
+    for (data, dict_p) in dict_src.iter().enumerate() {
+        assert!(test_trie.store(dict_p.key, data as i32));
+    }
+
+    msg_step("Remapping to a wider alpha map");
+    let mut wide_map = AlphaMap::default();
+    wide_map.add_range('0' as u32, '9' as u32)?;
+    wide_map.add_range('a' as u32, 'z' as u32)?;
+    let mut wide_trie = test_trie.remap_alphabet(&wide_map)?;
+    for (data, dict_p) in dict_src.iter().enumerate() {
+        assert_eq!(
+            wide_trie.retrieve(dict_p.key),
+            Some(data as i32),
+            "Failed to retrieve key '{:?}' after remap",
+            dict_p.key
+        );
+    }
+
+    msg_step("Storing a key that only the new map covers");
+    let key = alpha_key("abc123");
+    let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+    assert!(!test_trie.store(key, 99));
+    assert!(wide_trie.store(key, 99));
+    assert_eq!(wide_trie.retrieve(key), Some(99));
+    Ok(())
+}
+
+#[test]
+fn test_remap_alphabet_uncovered_char() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    for (word, data) in [("abc", 1), ("abz", 2)] {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data));
+    }
+
+    msg_step("Remapping to an alpha map without 'z'");
+    let mut narrow_map = AlphaMap::default();
+    narrow_map.add_range('a' as u32, 'y' as u32)?;
+    let err = test_trie.remap_alphabet(&narrow_map).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    Ok(())
+}
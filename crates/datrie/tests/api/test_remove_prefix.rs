@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaStr, DatrieResult, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+fn random_word(rng: &mut impl Rng) -> String {
+    let len = rng.gen_range(0..7);
+    (0..len).map(|_| rng.gen_range('a'..='d')).collect()
+}
+
+fn check_model(test_trie: &Trie, model: &BTreeMap<String, i32>) {
+    let entries: Vec<(String, i32)> = test_trie.iter().map(|(k, d)| (to_string(&k), d)).collect();
+    let expected: Vec<(String, i32)> = model.iter().map(|(k, &d)| (k.clone(), d)).collect();
+    assert_eq!(entries, expected);
+    assert_eq!(test_trie.len(), model.len());
+}
+
+#[test]
+fn test_remove_prefix() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    for (data, word) in ["app", "apple", "applet", "apply", "banana"]
+        .iter()
+        .enumerate()
+    {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+    let mut remove_prefix = |word: &str| {
+        let key = alpha_key(word);
+        test_trie.remove_prefix(AlphaStr::from_slice_with_nul(&key).unwrap())
+    };
+
+    msg_step("Removing prefixes");
+    assert_eq!(remove_prefix("apple"), 2);
+    assert_eq!(remove_prefix("apple"), 0);
+    assert_eq!(remove_prefix("ban"), 1);
+    assert_eq!(remove_prefix("c"), 0);
+    assert_eq!(remove_prefix("APP"), 0);
+    let words: Vec<String> = test_trie.iter().map(|(k, _)| to_string(&k)).collect();
+    assert_eq!(words, ["app", "apply"]);
+
+    msg_step("Removing everything");
+    let key = alpha_key("");
+    assert_eq!(
+        test_trie.remove_prefix(AlphaStr::from_slice_with_nul(&key).unwrap()),
+        2
+    );
+    assert!(test_trie.is_empty());
+    let key = alpha_key("apple");
+    assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), 7));
+    assert_eq!(
+        test_trie.retrieve(AlphaStr::from_slice_with_nul(&key).unwrap()),
+        Some(7)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_remove_prefix_against_btree_map() -> DatrieResult<()> {
+    let mut rng = rand::thread_rng();
+    for key_counting in [false, true] {
+        msg_step("Preparing random trie");
+        let mut test_trie = en_trie_new()?;
+        test_trie.set_key_counting(key_counting);
+        let mut model = BTreeMap::new();
+        for round in 0..20 {
+            for data in 0..60 {
+                let word = random_word(&mut rng);
+                let key = alpha_key(&word);
+                test_trie.insert(
+                    AlphaStr::from_slice_with_nul(&key).unwrap(),
+                    round * 100 + data,
+                );
+                model.insert(word, round * 100 + data);
+            }
+
+            msg_step("Removing a random prefix");
+            let len = rng.gen_range(0..4);
+            let prefix: String = (0..len).map(|_| rng.gen_range('a'..='d')).collect();
+            let key = alpha_key(&prefix);
+            let removed = test_trie.remove_prefix(AlphaStr::from_slice_with_nul(&key).unwrap());
+            let before = model.len();
+            model.retain(|word, _| !word.starts_with(&prefix));
+            assert_eq!(removed, before - model.len(), "prefix {:?}", prefix);
+            check_model(&test_trie, &model);
+        }
+
+        msg_step("Reloading the trie");
+        let mut buf = Vec::new();
+        test_trie.serialize_safe(&mut buf)?;
+        let loaded = Trie::fread_safe(&mut std::io::Cursor::new(buf))?;
+        check_model(&loaded, &model);
+    }
+    Ok(())
+}
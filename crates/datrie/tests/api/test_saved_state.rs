@@ -0,0 +1,89 @@
+use std::io::Cursor;
+
+use datrie::{AlphaChar, AlphaStr, DatrieResult, ErrorKind, Trie, TrieState, DA_TRUE};
+
+use crate::utils::{alpha_key, en_trie_new, msg_step};
+
+/// Walk `text` from `state`, returning whether every char was walkable.
+unsafe fn walk_text(state: *mut TrieState, text: &str) -> bool {
+    text.chars()
+        .all(|c| TrieState::walk(state, c as AlphaChar) == DA_TRUE)
+}
+
+#[test]
+fn test_saved_state() -> DatrieResult<()> {
+    unsafe {
+        msg_step("Preparing trie");
+        let mut test_trie = en_trie_new()?;
+        for (data, word) in ["stream", "streaming", "strong"].iter().enumerate() {
+            let key = alpha_key(word);
+            assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+        }
+        let mut buf = Vec::new();
+        test_trie.serialize_safe(&mut buf)?;
+
+        msg_step("Saving states in the double-array and in a tail");
+        let state = test_trie.root();
+        assert!(walk_text(state, "str"));
+        let branch_bytes = test_trie.save_state(&*state)?;
+        assert!(walk_text(state, "eami"));
+        let tail_bytes = test_trie.save_state(&*state)?;
+        TrieState::free(state);
+
+        msg_step("Continuing the walks in a reloaded trie");
+        let loaded = Trie::fread_safe(&mut Cursor::new(&buf))?;
+        assert_eq!(loaded.fingerprint(), test_trie.fingerprint());
+        let mut state = loaded.load_state(&branch_bytes)?;
+        assert!(walk_text(&mut state, "ong"));
+        assert!(TrieState::is_terminal(&state));
+        assert_eq!(TrieState::get_data(&state), 2);
+        let mut state = loaded.load_state(&tail_bytes)?;
+        assert!(!TrieState::is_terminal(&state));
+        assert!(walk_text(&mut state, "ng"));
+        assert!(TrieState::is_terminal(&state));
+        assert_eq!(TrieState::get_data(&state), 1);
+
+        msg_step("Rejecting states of another trie");
+        let state = loaded.root();
+        assert_eq!(
+            test_trie.save_state(&*state).err().map(|e| e.kind()),
+            Some(ErrorKind::InvalidArgument)
+        );
+        TrieState::free(state);
+        let key = alpha_key("strobe");
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), 3));
+        assert_ne!(loaded.fingerprint(), test_trie.fingerprint());
+        assert_eq!(
+            test_trie.load_state(&branch_bytes).err().map(|e| e.kind()),
+            Some(ErrorKind::InvalidArgument)
+        );
+
+        msg_step("Rejecting malformed states");
+        let mut bad_flag = tail_bytes.clone();
+        bad_flag[16] = 2;
+        let mut bad_index = tail_bytes.clone();
+        bad_index[8..12].copy_from_slice(&1000i32.to_be_bytes());
+        for bytes in [&tail_bytes[..10], &bad_flag[..], &bad_index[..]] {
+            assert_eq!(
+                loaded.load_state(bytes).err().map(|e| e.kind()),
+                Some(ErrorKind::InvalidArgument)
+            );
+        }
+
+        msg_step("Rejecting states in free tail blocks");
+        let key = alpha_key("strobe");
+        let strobe = AlphaStr::from_slice_with_nul(&key).unwrap();
+        let strobe_id = test_trie.lookup_id(strobe).unwrap();
+        assert!(test_trie.delete(strobe));
+        let state = test_trie.root();
+        let mut free_block = test_trie.save_state(&*state)?;
+        TrieState::free(state);
+        free_block[8..12].copy_from_slice(&strobe_id.index().to_be_bytes());
+        free_block[16] = 1;
+        assert_eq!(
+            test_trie.load_state(&free_block).err().map(|e| e.kind()),
+            Some(ErrorKind::InvalidArgument)
+        );
+    }
+    Ok(())
+}
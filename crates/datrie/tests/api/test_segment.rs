@@ -0,0 +1,72 @@
+use datrie::segment::{Fallback, Segment, Segmenter};
+use datrie::{AlphaMap, AlphaStr, DatrieResult, Trie};
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, text_of};
+
+fn trie_of(mut trie: Trie, words: &[&str]) -> Trie {
+    for (data, word) in words.iter().enumerate() {
+        let key = alpha_key(word);
+        assert!(trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+    trie
+}
+
+fn words_of<'a>(text: &'a str, segments: &[Segment]) -> Vec<&'a str> {
+    segments.iter().map(|s| &text[s.start..s.end]).collect()
+}
+
+#[test]
+fn test_segment_greedy_and_min_words() -> DatrieResult<()> {
+    msg_step("Preparing dictionary");
+    let words = ["a", "end", "me", "men", "mend", "the", "them", "theme"];
+    let test_trie = trie_of(en_trie_new()?, &words);
+    let segmenter = Segmenter::new(&test_trie);
+
+    msg_step("Greedy maximal matching");
+    let text = "themend";
+    let segments = segmenter.greedy(&text_of(text));
+    assert_eq!(words_of(text, &segments), ["theme", "n", "d"]);
+    assert_eq!(segments[0].data, Some(7));
+    assert_eq!(segments[1].data, None);
+
+    msg_step("Minimum word count");
+    let segments = segmenter.min_words(&text_of(text));
+    assert_eq!(words_of(text, &segments), ["the", "mend"]);
+    assert_eq!(
+        segments.iter().map(|s| s.data).collect::<Vec<_>>(),
+        [Some(5), Some(4)]
+    );
+
+    msg_step("Merging unknown runs");
+    let text = "thexyzmen";
+    let segmenter = segmenter.with_fallback(Fallback::MergeRun);
+    for segments in [
+        segmenter.greedy(&text_of(text)),
+        segmenter.min_words(&text_of(text)),
+    ] {
+        assert_eq!(words_of(text, &segments), ["the", "xyz", "men"]);
+        assert_eq!(segments[1].data, None);
+    }
+    assert!(segmenter.greedy(&[]).is_empty());
+    assert!(segmenter.min_words(&[]).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_segment_thai_byte_offsets() -> DatrieResult<()> {
+    msg_step("Preparing Thai dictionary");
+    let mut alpha_map = AlphaMap::default();
+    alpha_map.add_range(0x0e00, 0x0e7f)?;
+    let words = ["ไป", "ตลาด", "ตา", "ลาด"];
+    let test_trie = trie_of(Trie::new(&alpha_map)?, &words);
+    let segmenter = Segmenter::new(&test_trie);
+
+    msg_step("Segmenting with byte offsets");
+    let text = "ไปตลาด";
+    for segments in [segmenter.greedy_str(text), segmenter.min_words_str(text)] {
+        assert_eq!(words_of(text, &segments), ["ไป", "ตลาด"]);
+        assert_eq!((segments[1].start, segments[1].end), (6, 18));
+        assert_eq!(segments[1].data, Some(1));
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeSet;
+use std::io::Cursor;
+
+use datrie::{AlphaChar, AlphaStr, DatrieResult, ErrorKind, Trie, TrieSet};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_alpha_map_new, en_trie_new, msg_step, to_string};
+
+#[test]
+fn test_set_insert_remove() -> DatrieResult<()> {
+    msg_step("Preparing set");
+    let mut set = TrieSet::new(&en_alpha_map_new()?)?;
+    let key = |word: &str| alpha_key(word);
+
+    msg_step("Inserting and removing keys");
+    for word in ["cat", "car", "cart", "dog"] {
+        assert!(set.insert(AlphaStr::from_slice_with_nul(&key(word)).unwrap())?);
+    }
+    assert!(!set.insert(AlphaStr::from_slice_with_nul(&key("car")).unwrap())?);
+    assert!(set.contains(AlphaStr::from_slice_with_nul(&key("cart")).unwrap()));
+    assert!(!set.contains(AlphaStr::from_slice_with_nul(&key("ca")).unwrap()));
+    assert!(set.remove(AlphaStr::from_slice_with_nul(&key("cat")).unwrap()));
+    assert!(!set.remove(AlphaStr::from_slice_with_nul(&key("cat")).unwrap()));
+    assert_eq!(set.len(), 3);
+    assert_eq!(
+        set.insert(AlphaStr::from_slice_with_nul(&key("Cat")).unwrap())
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+
+    msg_step("Iterating");
+    let words: Vec<String> = set.iter().map(|k| to_string(&k)).collect();
+    assert_eq!(words, ["car", "cart", "dog"]);
+    let prefix = key("car");
+    let words: Vec<String> = set
+        .iter_prefix(AlphaStr::from_slice_with_nul(&prefix).unwrap())
+        .map(|k| to_string(&k))
+        .collect();
+    assert_eq!(words, ["car", "cart"]);
+    let words: Vec<String> = (&set).into_iter().rev().map(|k| to_string(&k)).collect();
+    assert_eq!(words, ["dog", "cart", "car"]);
+    Ok(())
+}
+
+#[test]
+fn test_set_from_iter() -> DatrieResult<()> {
+    msg_step("Collecting a set");
+    let mut set: TrieSet = ["pear", "apple", "Äpfel", "pea"].into_iter().collect();
+    set.extend(vec!["zebra".to_string(), "apple".to_string()]);
+    let words: Vec<String> = set.iter().map(|k| to_string(&k)).collect();
+    let mut expected = vec!["pear", "apple", "Äpfel", "pea", "zebra"];
+    expected.sort();
+    assert_eq!(words, expected);
+    let key = alpha_key("q");
+    assert!(!set.contains(AlphaStr::from_slice_with_nul(&key).unwrap()));
+    Ok(())
+}
+
+#[test]
+fn test_set_from_iter_wide_alphabet() {
+    msg_step("Collecting keys with 255 distinct chars");
+    let words: Vec<String> = (0..255)
+        .map(|i| format!("{}", char::from_u32(0x4e00 + i).unwrap()))
+        .collect();
+    let mut set: TrieSet = words.iter().collect();
+    assert_eq!(set.len(), 255);
+    for word in &words {
+        let key: Vec<AlphaChar> = word.chars().map(|c| c as AlphaChar).chain([0]).collect();
+        assert!(set.contains(AlphaStr::from_slice_with_nul(&key).unwrap()));
+    }
+
+    msg_step("Adding a 256th char");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        set.extend(["\u{4e00}\u{4fff}"]);
+    }));
+    assert!(result.is_err());
+    assert_eq!(set.len(), 255);
+}
+
+#[test]
+fn test_set_serialization() -> DatrieResult<()> {
+    msg_step("Preparing random set");
+    let mut rng = rand::thread_rng();
+    let mut set = TrieSet::new(&en_alpha_map_new()?)?;
+    let mut trie = en_trie_new()?;
+    let mut model = BTreeSet::new();
+    for _ in 0..300 {
+        let len = rng.gen_range(1..8);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='z')).collect();
+        let key = alpha_key(&word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        set.insert(key)?;
+        trie.store(key, 0);
+        model.insert(word);
+    }
+
+    msg_step("Comparing prefix iteration");
+    for prefix in ["", "a", "ab", "zz"] {
+        let key = alpha_key(prefix);
+        let words: Vec<String> = set
+            .iter_prefix(AlphaStr::from_slice_with_nul(&key).unwrap())
+            .map(|k| to_string(&k))
+            .collect();
+        let expected: Vec<String> = model
+            .iter()
+            .filter(|word| word.starts_with(prefix))
+            .cloned()
+            .collect();
+        assert_eq!(words, expected);
+    }
+
+    msg_step("Saving and reloading");
+    let mut buf = Vec::new();
+    set.serialize_safe(&mut buf)?;
+    assert!(!set.is_dirty());
+    assert_eq!(buf.len(), set.get_serialized_size());
+    // one tail block per key, as nothing was deleted
+    assert_eq!(buf.len() + 4 * trie.len(), trie.get_serialized_size());
+    let loaded = TrieSet::fread_safe(&mut Cursor::new(&buf))?;
+    let words: Vec<String> = loaded.iter().map(|k| to_string(&k)).collect();
+    assert_eq!(words, model.into_iter().collect::<Vec<_>>());
+    assert_eq!(
+        Trie::fread_safe(&mut Cursor::new(&buf))
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidFileSignature)
+    );
+    Ok(())
+}
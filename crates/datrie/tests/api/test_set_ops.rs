@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaChar, AlphaMap, AlphaStr, DatrieResult, ErrorKind, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+fn new_trie(alpha_map: &AlphaMap, entries: &[(&str, i32)]) -> DatrieResult<Trie> {
+    let mut trie = Trie::new(alpha_map)?;
+    for &(word, data) in entries {
+        let key = alpha_key(word);
+        assert!(trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data));
+    }
+    Ok(trie)
+}
+
+fn entries(trie: &Trie) -> BTreeMap<String, i32> {
+    trie.iter().map(|(k, d)| (to_string(&k), d)).collect()
+}
+
+fn random_model(rng: &mut impl Rng) -> BTreeMap<String, i32> {
+    (0..80)
+        .map(|data| {
+            let len = rng.gen_range(0..5);
+            let word: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+            (word, data)
+        })
+        .collect()
+}
+
+fn model_trie(model: &BTreeMap<String, i32>) -> DatrieResult<Trie> {
+    let mut trie = en_trie_new()?;
+    for (word, &data) in model {
+        let key = alpha_key(word);
+        assert!(trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data));
+    }
+    Ok(trie)
+}
+
+#[test]
+fn test_set_ops_against_btree_map() -> DatrieResult<()> {
+    let mut rng = rand::thread_rng();
+    for _ in 0..10 {
+        msg_step("Preparing random tries");
+        let left = random_model(&mut rng);
+        let right = random_model(&mut rng);
+        let left_trie = model_trie(&left)?;
+        let right_trie = model_trie(&right)?;
+
+        msg_step("Merging");
+        let mut merged_trie = model_trie(&left)?;
+        merged_trie.merge_from(&right_trie, |_, ours, theirs| ours + theirs)?;
+        let mut merged = left.clone();
+        for (word, &data) in &right {
+            *merged.entry(word.clone()).or_insert(0) += data;
+        }
+        assert_eq!(entries(&merged_trie), merged);
+
+        msg_step("Building set operations");
+        let intersection: BTreeMap<_, _> = left
+            .iter()
+            .filter(|(word, _)| right.contains_key(*word))
+            .map(|(word, &data)| (word.clone(), data))
+            .collect();
+        assert_eq!(entries(&left_trie.intersection(&right_trie)?), intersection);
+        let difference: BTreeMap<_, _> = left
+            .iter()
+            .filter(|(word, _)| !right.contains_key(*word))
+            .map(|(word, &data)| (word.clone(), data))
+            .collect();
+        assert_eq!(entries(&left_trie.difference(&right_trie)?), difference);
+        let mut symmetric_difference = difference.clone();
+        symmetric_difference.extend(
+            right
+                .iter()
+                .filter(|(word, _)| !left.contains_key(*word))
+                .map(|(word, &data)| (word.clone(), data)),
+        );
+        assert_eq!(
+            entries(&left_trie.symmetric_difference(&right_trie)?),
+            symmetric_difference
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_set_ops_alpha_maps() -> DatrieResult<()> {
+    msg_step("Preparing tries with different alpha maps");
+    let mut wide_map = AlphaMap::default();
+    wide_map.add_range('A' as AlphaChar, 'Z' as AlphaChar)?;
+    wide_map.add_range('a' as AlphaChar, 'z' as AlphaChar)?;
+    let mut narrow_map = AlphaMap::default();
+    narrow_map.add_range('a' as AlphaChar, 'm' as AlphaChar)?;
+    let mut base = new_trie(&narrow_map, &[("bad", 1), ("cab", 2)])?;
+    let overlay = new_trie(&wide_map, &[("cab", 20), ("deal", 30)])?;
+    let foreign = new_trie(&wide_map, &[("Zebra", 40)])?;
+
+    msg_step("Merging through a remapped alpha map");
+    base.merge_from(&overlay, |key, _, theirs| {
+        assert_eq!(to_string(key), "cab");
+        theirs
+    })?;
+    let expected: BTreeMap<String, i32> = [("bad", 1), ("cab", 20), ("deal", 30)]
+        .iter()
+        .map(|&(word, data)| (word.to_string(), data))
+        .collect();
+    assert_eq!(entries(&base), expected);
+    assert_eq!(base.alpha_map, narrow_map);
+
+    msg_step("Failing on keys outside the alpha map");
+    let err = base.merge_from(&foreign, |_, ours, _| ours).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(entries(&base), expected);
+    assert_eq!(
+        base.difference(&foreign).err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+    let with_foreign = foreign.symmetric_difference(&base)?;
+    assert_eq!(with_foreign.len(), 4);
+    Ok(())
+}
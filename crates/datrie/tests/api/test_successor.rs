@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use datrie::{AlphaStr, DatrieResult};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+#[test]
+fn test_successor_predecessor() -> DatrieResult<()> {
+    msg_step("Preparing trie");
+    let mut test_trie = en_trie_new()?;
+    for (data, word) in ["pool", "prize", "preview", "prepare", "produce"]
+        .iter()
+        .enumerate()
+    {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data as i32));
+    }
+
+    msg_step("Looking up neighbours");
+    let neighbours = |word: &str| {
+        let key = alpha_key(word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        (
+            test_trie.predecessor(key).map(|(k, _)| to_string(&k)),
+            test_trie.successor(key).map(|(k, _)| to_string(&k)),
+        )
+    };
+    assert_eq!(
+        neighbours("prize"),
+        (Some("preview".into()), Some("produce".into()))
+    );
+    assert_eq!(
+        neighbours("pri"),
+        (Some("preview".into()), Some("prize".into()))
+    );
+    assert_eq!(
+        neighbours("prizes"),
+        (Some("prize".into()), Some("produce".into()))
+    );
+    assert_eq!(neighbours("poo"), (None, Some("pool".into())));
+    assert_eq!(neighbours("zoo"), (Some("produce".into()), None));
+    assert_eq!(neighbours(""), (None, Some("pool".into())));
+
+    msg_step("Looking up a key outside the alpha map");
+    let key = alpha_key("P");
+    assert_eq!(
+        test_trie.successor(AlphaStr::from_slice_with_nul(&key).unwrap()),
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn test_successor_against_btree_map() -> DatrieResult<()> {
+    msg_step("Preparing random trie");
+    let mut rng = rand::thread_rng();
+    let random_word = |rng: &mut rand::rngs::ThreadRng| -> String {
+        let len = rng.gen_range(0..7);
+        (0..len).map(|_| rng.gen_range('a'..='c')).collect()
+    };
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for data in 0..150 {
+        let word = random_word(&mut rng);
+        let key = alpha_key(&word);
+        test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+        model.insert(word, data);
+    }
+
+    msg_step("Comparing neighbours");
+    for _ in 0..500 {
+        let word = random_word(&mut rng);
+        let key = alpha_key(&word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        let expected = model
+            .range::<String, _>((Bound::Excluded(&word), Bound::Unbounded))
+            .next()
+            .map(|(k, &d)| (k.clone(), d));
+        let found = test_trie.successor(key).map(|(k, d)| (to_string(&k), d));
+        assert_eq!(found, expected, "successor of {:?}", word);
+        let expected = model
+            .range::<String, _>(..&word)
+            .next_back()
+            .map(|(k, &d)| (k.clone(), d));
+        let found = test_trie.predecessor(key).map(|(k, d)| (to_string(&k), d));
+        assert_eq!(found, expected, "predecessor of {:?}", word);
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use datrie::{AlphaChar, AlphaMap, AlphaStr, DatrieResult, ErrorKind, SuffixIndex, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, text_of, to_string};
+
+fn words(entries: Vec<(Vec<AlphaChar>, i32)>) -> Vec<String> {
+    entries.iter().map(|(k, _)| to_string(k)).collect()
+}
+
+#[test]
+fn test_suffix_index_ends_with() -> DatrieResult<()> {
+    msg_step("Preparing suffix index");
+    let mut test_trie = en_trie_new()?;
+    let key = alpha_key("sing");
+    assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), 1));
+    let mut index = SuffixIndex::new(test_trie)?;
+    for (data, word) in ["singing", "bring", "ringer", "thing"].iter().enumerate() {
+        let key = alpha_key(word);
+        assert!(index.store(
+            AlphaStr::from_slice_with_nul(&key).unwrap(),
+            data as i32 + 2
+        ));
+    }
+
+    msg_step("Querying suffixes");
+    let ing = alpha_key("ing");
+    let ing = AlphaStr::from_slice_with_nul(&ing).unwrap();
+    assert_eq!(
+        words(index.ends_with(ing)),
+        ["bring", "sing", "singing", "thing"]
+    );
+    let er = alpha_key("er");
+    assert_eq!(
+        index.ends_with(AlphaStr::from_slice_with_nul(&er).unwrap()),
+        [(text_of("ringer"), 4)]
+    );
+    let all = alpha_key("");
+    assert_eq!(
+        index
+            .ends_with(AlphaStr::from_slice_with_nul(&all).unwrap())
+            .len(),
+        5
+    );
+    assert_eq!(
+        index.contains_infix(ing).err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+
+    msg_step("Deleting keys");
+    let key = alpha_key("thing");
+    assert!(index.delete(AlphaStr::from_slice_with_nul(&key).unwrap()));
+    assert!(!index.delete(AlphaStr::from_slice_with_nul(&key).unwrap()));
+    assert_eq!(words(index.ends_with(ing)), ["bring", "sing", "singing"]);
+    assert_eq!(index.into_trie().len(), 4);
+    Ok(())
+}
+
+#[test]
+fn test_suffix_index_infix() -> DatrieResult<()> {
+    msg_step("Preparing random infix index");
+    let mut rng = rand::thread_rng();
+    let mut index = SuffixIndex::with_infix(en_trie_new()?)?;
+    assert!(index.is_infix());
+    let mut model = std::collections::BTreeMap::new();
+    for data in 0..200 {
+        let len = rng.gen_range(0..6);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+        let key = alpha_key(&word);
+        if rng.gen_bool(0.8) {
+            assert!(index.store(AlphaStr::from_slice_with_nul(&key).unwrap(), data));
+            model.insert(word, data);
+        } else {
+            let deleted = index.delete(AlphaStr::from_slice_with_nul(&key).unwrap());
+            assert_eq!(deleted, model.remove(&word).is_some());
+        }
+    }
+
+    msg_step("Comparing infix and suffix queries");
+    for _ in 0..100 {
+        let len = rng.gen_range(0..4);
+        let part: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+        let key = alpha_key(&part);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        let found: Vec<(String, i32)> = index
+            .contains_infix(key)?
+            .iter()
+            .map(|(k, d)| (to_string(k), *d))
+            .collect();
+        let expected: Vec<(String, i32)> = model
+            .iter()
+            .filter(|(word, _)| word.contains(&part))
+            .map(|(word, &data)| (word.clone(), data))
+            .collect();
+        assert_eq!(found, expected, "infix {:?}", part);
+        let found = words(index.ends_with(key));
+        let expected: Vec<String> = model
+            .keys()
+            .filter(|word| word.ends_with(&part))
+            .cloned()
+            .collect();
+        assert_eq!(found, expected, "suffix {:?}", part);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_suffix_index_invalid_queries() -> DatrieResult<()> {
+    msg_step("Preparing infix index");
+    let mut index = SuffixIndex::with_infix(en_trie_new()?)?;
+    for word in ["ab", "ba"] {
+        let key = alpha_key(word);
+        assert!(index.store(AlphaStr::from_slice_with_nul(&key).unwrap(), 1));
+    }
+
+    msg_step("Querying chars outside the alpha map");
+    // the separator is the first char outside the alpha map
+    for query in ["\u{1}", "a\u{1}", "\u{1}b", "A"] {
+        let key = alpha_key(query);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        assert_eq!(
+            index.contains_infix(key).err().map(|e| e.kind()),
+            Some(ErrorKind::InvalidArgument),
+            "infix {:?}",
+            query
+        );
+        assert!(index.ends_with(key).is_empty(), "suffix {:?}", query);
+    }
+
+    msg_step("Indexing a full alphabet by infix");
+    let mut alpha_map = AlphaMap::default();
+    alpha_map.add_range(1, 255)?;
+    assert_eq!(
+        SuffixIndex::with_infix(Trie::new(&alpha_map)?)
+            .err()
+            .map(|e| e.kind()),
+        Some(ErrorKind::InvalidArgument)
+    );
+    let mut alpha_map = AlphaMap::default();
+    alpha_map.add_range(1, 254)?;
+    assert!(SuffixIndex::with_infix(Trie::new(&alpha_map)?)?.is_infix());
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use datrie::{AlphaStr, DatrieResult, Trie};
+use rand::Rng;
+
+use crate::utils::{alpha_key, en_trie_new, msg_step, to_string};
+
+fn top_k(trie: &Trie, prefix: &str, k: usize) -> Vec<(String, i32)> {
+    let key = alpha_key(prefix);
+    trie.top_k_by_data(AlphaStr::from_slice_with_nul(&key).unwrap(), k)
+        .into_iter()
+        .map(|(key, data)| (to_string(&key), data))
+        .collect()
+}
+
+fn brute_top_k(model: &BTreeMap<String, i32>, prefix: &str, k: usize) -> Vec<(String, i32)> {
+    let mut found: Vec<(String, i32)> = model
+        .iter()
+        .filter(|(word, _)| word.starts_with(prefix))
+        .map(|(word, &data)| (word.clone(), data))
+        .collect();
+    found.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    found.truncate(k);
+    found
+}
+
+#[test]
+fn test_top_k_by_data() -> DatrieResult<()> {
+    msg_step("Preparing word frequencies");
+    let mut test_trie = en_trie_new()?;
+    for (word, freq) in [
+        ("car", 50),
+        ("card", 20),
+        ("care", 70),
+        ("cart", 20),
+        ("cat", 90),
+        ("dog", 99),
+    ] {
+        let key = alpha_key(word);
+        assert!(test_trie.store(AlphaStr::from_slice_with_nul(&key).unwrap(), freq));
+    }
+
+    msg_step("Querying top completions");
+    let pairs = |v: &[(&str, i32)]| -> Vec<(String, i32)> {
+        v.iter().map(|&(w, d)| (w.to_string(), d)).collect()
+    };
+    assert_eq!(
+        top_k(&test_trie, "ca", 2),
+        pairs(&[("cat", 90), ("care", 70)])
+    );
+    assert_eq!(
+        top_k(&test_trie, "car", 10),
+        pairs(&[("care", 70), ("car", 50), ("card", 20), ("cart", 20)])
+    );
+    assert_eq!(top_k(&test_trie, "do", 3), pairs(&[("dog", 99)]));
+    assert_eq!(top_k(&test_trie, "dogs", 3), pairs(&[]));
+    assert_eq!(top_k(&test_trie, "c", 0), pairs(&[]));
+
+    msg_step("Querying with a coarser score");
+    let key = alpha_key("car");
+    let by_tens =
+        test_trie.top_k_completions(AlphaStr::from_slice_with_nul(&key).unwrap(), 3, |data| {
+            data / 10
+        });
+    let by_tens: Vec<(String, i32)> = by_tens
+        .into_iter()
+        .map(|(key, data)| (to_string(&key), data))
+        .collect();
+    assert_eq!(by_tens, pairs(&[("care", 70), ("car", 50), ("card", 20)]));
+
+    msg_step("Querying after modification");
+    let key = alpha_key("card");
+    *test_trie
+        .get_mut(AlphaStr::from_slice_with_nul(&key).unwrap())
+        .unwrap() = 100;
+    let key = alpha_key("cat");
+    assert_eq!(
+        test_trie.remove(AlphaStr::from_slice_with_nul(&key).unwrap()),
+        Some(90)
+    );
+    assert_eq!(
+        top_k(&test_trie, "ca", 2),
+        pairs(&[("card", 100), ("care", 70)])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_top_k_against_brute_force() -> DatrieResult<()> {
+    msg_step("Preparing random trie");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for _ in 0..500 {
+        let len = rng.gen_range(1..7);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+        let data = rng.gen_range(0..50);
+        let key = alpha_key(&word);
+        test_trie.insert(AlphaStr::from_slice_with_nul(&key).unwrap(), data);
+        model.insert(word, data);
+    }
+
+    msg_step("Comparing with brute force");
+    for _ in 0..200 {
+        let len = rng.gen_range(0..4);
+        let prefix: String = (0..len).map(|_| rng.gen_range('a'..='c')).collect();
+        let k = rng.gen_range(0..10);
+        assert_eq!(
+            top_k(&test_trie, &prefix, k),
+            brute_top_k(&model, &prefix, k),
+            "prefix '{}', k {}",
+            prefix,
+            k
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_top_k_while_modifying() -> DatrieResult<()> {
+    msg_step("Interleaving queries with stores and deletes");
+    let mut rng = rand::thread_rng();
+    let mut test_trie = en_trie_new()?;
+    let mut model = BTreeMap::new();
+    for round in 0..2000 {
+        let len = rng.gen_range(1..6);
+        let word: String = (0..len).map(|_| rng.gen_range('a'..='d')).collect();
+        let key = alpha_key(&word);
+        let key = AlphaStr::from_slice_with_nul(&key).unwrap();
+        let data = rng.gen_range(0..100);
+        match rng.gen_range(0..6) {
+            0 | 1 => {
+                test_trie.insert(key, data);
+                model.insert(word.clone(), data);
+            }
+            2 => {
+                assert_eq!(test_trie.remove(key), model.remove(&word));
+            }
+            3 => {
+                if let Some(value) = test_trie.get_mut(key) {
+                    *value = data;
+                    model.insert(word.clone(), data);
+                }
+            }
+            4 => {
+                *test_trie.entry(key)?.or_insert(0) += data;
+                *model.entry(word.clone()).or_insert(0) += data;
+            }
+            _ => {
+                if round % 50 == 0 {
+                    let prefix = &word[..1];
+                    let key = alpha_key(prefix);
+                    test_trie.remove_prefix(AlphaStr::from_slice_with_nul(&key).unwrap());
+                    model.retain(|w, _| !w.starts_with(prefix));
+                }
+            }
+        }
+        let prefix = &word[..rng.gen_range(0..=word.len().min(2))];
+        let k = rng.gen_range(1..6);
+        assert_eq!(
+            top_k(&test_trie, prefix, k),
+            brute_top_k(&model, prefix, k),
+            "round {}, prefix '{}', k {}",
+            round,
+            prefix,
+            k
+        );
+    }
+    Ok(())
+}
@@ -72,7 +72,8 @@ pub const TRIE_DATA_READ: TrieData = 2;
 // #include "utils.h"
 
 use datrie::{
-    AlphaStr, DatrieResult, {alpha_char_strcmp, AlphaChar, AlphaMap}, {Trie, TrieData},
+    AlphaStr, DatrieResult, {alpha_char_strcmp, AlphaChar, AlphaMap},
+    {Trie, TrieData, TrieIterator, TrieState, DA_TRUE},
 };
 
 // /*---------------------*
@@ -99,6 +100,41 @@ pub fn en_trie_new() -> DatrieResult<Trie> {
     Ok(en_trie)
 }
 
+/// Build a nul-terminated `AlphaChar` key from a `&str`.
+pub fn alpha_key(s: &str) -> Vec<AlphaChar> {
+    s.chars().map(|c| c as AlphaChar).chain([0]).collect()
+}
+
+/// Build an `AlphaChar` text (without terminator) from a `&str`.
+pub fn text_of(s: &str) -> Vec<AlphaChar> {
+    s.chars().map(|c| c as AlphaChar).collect()
+}
+
+/// Convert a key (without terminator) back to a `String`.
+pub fn to_string(key: &[AlphaChar]) -> String {
+    key.iter().map(|&c| char::from_u32(c).unwrap()).collect()
+}
+
+/// Collect the keys (without terminator) and data of `trie` in iteration order.
+pub fn trie_entries(trie: &Trie) -> Vec<(Vec<AlphaChar>, TrieData)> {
+    let mut entries = Vec::new();
+    unsafe {
+        let root = trie.root();
+        let iter = TrieIterator::new(root);
+        while TrieIterator::next(iter) == DA_TRUE {
+            let key = TrieIterator::get_key(iter);
+            entries.push((
+                AlphaStr::from_ptr(key).to_slice().to_vec(),
+                TrieIterator::get_data(iter),
+            ));
+            libc::free(key as *mut libc::c_void);
+        }
+        TrieIterator::free(iter);
+        TrieState::free(root);
+    }
+    entries
+}
+
 // /*---------------------------*
 //  *  Dict source for testing  *
 //  *---------------------------*/